@@ -1,3 +1,6 @@
+// ドキュメントコメントもタブでインデントする
+#![allow(clippy::tabs_in_doc_comments)]
+
 pub mod process;
 pub mod profiler;
 pub mod profile_if;
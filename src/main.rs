@@ -4,6 +4,7 @@ use cpu_usage::profile_if::ProfileIF;
 use std::path::Path;
 use std::ffi::OsStr;
 
+#[allow(dead_code)]
 fn get_args_in_out() -> (String, String) {
 	//let args: Vec<String> = std::env::args_os().into_iter().map(|arg| arg.to_string_lossy().to_string()).collect();
 	//let args_os: Vec<std::ffi::OsString> = std::env::args_os().collect();
@@ -31,7 +32,7 @@ fn get_args_in_out() -> (String, String) {
 	let output = format!("{}/{}.plantuml", inp_parent, inp_stem);
 	//let output_path = Path::new(&output);
 
-	return (input, output);
+	(input, output)
 }
 
 
@@ -84,7 +85,7 @@ fn get_base_path(_inp: &String) -> String {
 	let inp_base = format!("{}/{}", inp_parent, inp_stem);
 	//let output_path = Path::new(&output);
 
-	return inp_base;
+	inp_base
 }
 
 
@@ -1,5 +1,19 @@
+#[allow(clippy::module_inception)]
 pub mod process;
 pub mod process_tracer;
 pub mod process_state;
 pub mod process_callback;
+pub mod process_policy;
+pub mod process_option;
+pub mod process_rand;
+pub mod process_jitter;
+pub mod process_arrival;
+pub mod process_resource;
+pub mod process_kernel;
+pub mod process_chain;
+pub mod process_sync;
+pub mod process_alarm;
+pub mod process_exectime;
+pub mod process_montecarlo;
+pub mod process_mode;
 //pub mod process_time;
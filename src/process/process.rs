@@ -1,6 +1,23 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
 use crate::process::process_state::ProcessState;
 use crate::process::process_callback::ProcessCallback;
+use crate::process::process_callback::ProcessLog;
+use crate::process::process_option::ProcessOption;
+use crate::process::process_jitter::ReleaseJitter;
+use crate::process::process_rand::ProcessRand;
+use crate::process::process_exectime::ExecTimeDist;
+use crate::process::process_mode::ModeParam;
+use crate::process::process_arrival::SporadicArrival;
+use crate::process::process_resource::CriticalSection;
+use crate::process::process_resource::MutexSection;
+use crate::process::process_resource::IntLockSection;
+use crate::process::process_chain::JobChain;
+use crate::process::process_sync::SyncPoint;
+use crate::process::process_resource::MutexRequest;
 
+#[derive(Clone)]
 pub enum ProcessKind {
 	INTR,
 	TASK,
@@ -14,15 +31,68 @@ pub struct Process<T>
 	pub kind: ProcessKind,			// プロセス種類
 	pub priority: i32,				// 優先度
 	pub multi_intr: bool,			// 多重割込み許可
-	time_cycle: i32,				// 起動周期
+	pub core: usize,				// 割り当てコア(グローバルスケジューリングでは前回実行コア)
+	time_cycle: i32,				// 起動周期(スポラディックでは最小到着間隔)
+	sporadic: Option<SporadicArrival>,	// スポラディック起動
+	act_limit: Option<i32>,			// 多重起動上限
+	time_deadline: i32,				// 相対デッドライン
+	release_jitter: Option<ReleaseJitter>,	// リリースジッタ
 	time_proc: Vec<i32>,			// 処理時間[Max,Ave1,Ave2,...]
 	time_proc_idx: usize,			// 処理時間選択idx
+	exec_time: Option<ExecTimeDist>,	// 処理時間分布
+	modes: HashMap<String, ModeParam>,	// モード別パラメータ
+	base_cycle: i32,				// 基本設定の起動周期
+	base_time_proc: Vec<i32>,		// 基本設定の処理時間
+	implicit_deadline: bool,		// 相対デッドライン未指定(起動周期に追従)
+	suspended: bool,				// 現在モードで起動しない
+	time_proc_cur: i32,				// 実行中ジョブの処理時間
+	time_proc_raw: i32,				// 実行中ジョブの処理時間(基準周波数)
+	ref_freq: Option<i32>,			// 処理時間の基準周波数(MHz)
+	cpu_freq: i32,					// CPU周波数(MHz), 0でスケーリングしない
+	time_quantum: Option<i32>,		// ラウンドロビンのタイムクォンタム
+	critical_sections: Vec<CriticalSection>,	// クリティカルセクション
+	mutex_sections: Vec<MutexSection>,	// ミューテックス占有区間
+	level: Option<i32>,				// 割り込みレベル
+	intlock_sections: Vec<IntLockSection>,	// 割り込み禁止区間
+	threshold: Option<i32>,			// プリエンプション閾値
+	sched_points: Vec<i32>,			// スケジュールポイント
+	pub trigger: Option<(String, Option<i32>)>,	// 起動トリガ(起動元プロセス名, ジョブ内処理時間)
+	external: bool,					// 外部起動(起動トリガ/アラーム/スケジュールテーブル)
+	triggers: Vec<(usize, Option<i32>)>,	// 自プロセスが起動するプロセス(プロセスidx, ジョブ内処理時間)
+	sync_points: Vec<SyncPoint>,	// 同期ポイント
 	pub name: String,			// プロセス名
 	// プロセス制御情報
 	state: ProcessState,			// 状態
 	timer_cycle: i32,				// 起動周期タイマ
+	cycle_cur: i32,					// 今回の起動間隔
 	pub timer_ready: i32,			// READY時間タイマ
+	pub ready_since: i32,			// 最後にREADYに遷移したCPU時間
 	timer_run: i32,					// RUNNING時間タイマ
+	timer_slice: i32,				// タイムスライス消費時間タイマ
+	timer_block: i32,				// BLOCKED時間タイマ
+	timer_evwait: i32,				// EVWAIT時間タイマ
+	overhead_remain: i32,			// オーバーヘッド残り時間(この間は処理時間を進めない)
+	overhead_job: i32,				// 実行中ジョブで消費したオーバーヘッド時間(マイグレーションコスト)
+	kernel_remain: i32,				// カーネル処理残り時間(この間は処理時間を進めない)
+	kernel_exit: i32,				// ジョブ完了時のカーネル処理時間(ISR出口)
+	kernel_exit_done: bool,			// 実行中ジョブでISR出口処理を開始済み
+	kernel_log: bool,				// カーネル処理中のためREADYとしてログ出力中
+	job_started: bool,				// 実行中ジョブがディスパッチ済み
+	inherit_priority: Option<i32>,	// 優先度継承による優先度
+	mutex_released: Vec<String>,	// ジョブ完了により解放したミューテックス
+	blocked_by: Option<i32>,		// 優先度逆転を起こしているブロック元プロセスID
+	blocked_since: i32,				// ブロック開始時のCPU時間
+	jitter_cur: i32,				// 次回起動のリリースジッタ
+	rand: ProcessRand,				// 乱数生成器
+	exec_rand: ProcessRand,			// 処理時間用乱数生成器
+	job_release: i32,				// 実行中ジョブの起動時刻
+	job_overrun: bool,				// 実行中ジョブで処理つぶれ発生済み
+	act_queue: VecDeque<(i32, JobChain)>,	// キューイングされた起動要求の起動時刻と起動元履歴
+	job_chain: JobChain,			// 実行中ジョブの起動元履歴
+	trigger_events: Vec<(usize, JobChain)>,	// 今回の時間経過で発生した起動トリガ
+	job_done: Option<JobChain>,		// 今回の時間経過で完了したジョブの起動元履歴
+	timer_blocked: i32,				// 実行中ジョブのブロッキング時間タイマ
+	timer_masked: i32,				// 実行中ジョブの割り込みマスク待ち時間タイマ
 	// ログ情報
 	log_cpu_time: i32,				// プロセス起動時CPU時間
 	log_cycle_delayed: bool,		// 処理遅延有無
@@ -30,107 +100,364 @@ pub struct Process<T>
 	// プロセス毎最大占有率
 	max_cpu_use_rate: f32,			// プロセス占有率:起動周期当たりに占める時間割合
 	max_cpu_time: i32,				// 最大占有率発生時のCPU時間
+	mode_max_cpu_use_rate: f32,		// 現在モードでの最大占有率
+	// 処理つぶれ/デッドライン統計
+	cnt_overrun: i32,				// 処理つぶれ発生回数
+	cnt_deadline_miss: i32,			// デッドラインミス回数
+	max_lateness: Option<i32>,		// 最大遅れ時間(完了時刻-絶対デッドライン)
+	// 多重起動統計
+	cnt_act_queued: i32,			// キューイングされた起動要求数
+	act_dropped: Vec<i32>,			// 破棄された起動要求の起動時刻
+	// 処理時間統計
+	cnt_exec: i32,					// 処理時間生成回数
+	min_exec: i32,					// 処理時間最小値
+	max_exec: i32,					// 処理時間最大値
+	total_exec: i64,				// 処理時間合計
+	// ブロッキング統計
+	max_blocked: i32,				// ジョブ毎ブロッキング時間の最大値
+	total_blocked: i32,				// ブロッキング時間の合計
+	// 優先度逆転統計
+	cnt_inversion: i32,				// 優先度逆転発生回数
+	max_inversion: i32,				// 優先度逆転時間の最大値
+	// マイグレーション統計
+	cnt_migration: i32,				// マイグレーション回数
+	// 同期待ち統計
+	cnt_evwait: i32,				// EVWAIT発生回数
+	max_evwait: i32,				// ジョブ毎EVWAIT時間の最大値
+	total_evwait: i32,				// EVWAIT時間の合計
+	// 割り込みレイテンシ統計
+	max_latency: i32,				// 起動から実行開始までの最大時間
+	max_masked: i32,				// ジョブ毎割り込みマスク待ち時間の最大値
+	total_masked: i32,				// 割り込みマスク待ち時間の合計
 }
 
 impl<T> Process<T>
 	where T: ProcessCallback
 {
 	// コンストラクタ
-	pub fn new(kind: ProcessKind, name: String, state: ProcessState, priority:i32, multi_intr:bool, time_cycle: i32, time_proc: Vec<i32>, cb: T) -> Process<T> {
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(kind: ProcessKind, name: String, state: ProcessState, priority:i32, multi_intr:bool, time_cycle: i32, time_proc: Vec<i32>, option: ProcessOption, cb: T) -> Process<T> {
 		Process{
 			id: -1,
-			kind: kind,
-			priority: priority,
-			multi_intr: multi_intr,
-			time_cycle: time_cycle,
-			time_proc: time_proc,
+			kind,
+			priority,
+			multi_intr,
+			core: option.core.unwrap_or(0),
+			time_cycle,
+			time_deadline: option.time_deadline.unwrap_or(time_cycle),
+			release_jitter: option.release_jitter,
+			sporadic: option.sporadic,
+			act_limit: option.act_limit,
+			time_proc: time_proc.clone(),
 			time_proc_idx: 0,
-			name: name,
+			exec_time: option.exec_time,
+			modes: option.modes,
+			base_cycle: time_cycle,
+			base_time_proc: time_proc,
+			implicit_deadline: option.time_deadline.is_none(),
+			suspended: false,
+			time_proc_cur: 0,
+			time_proc_raw: 0,
+			ref_freq: option.ref_freq,
+			cpu_freq: 0,
+			time_quantum: option.time_quantum,
+			critical_sections: option.critical_sections,
+			mutex_sections: option.mutex_sections,
+			level: option.level,
+			intlock_sections: option.intlock_sections,
+			threshold: option.threshold,
+			sched_points: option.sched_points,
+			external: option.trigger.is_some(),
+			trigger: option.trigger,
+			triggers: vec![],
+			sync_points: option.sync_points,
+			name,
 			state,
-			timer_cycle: 0,
+			// 初回起動時刻指定があれば起動周期タイマをずらしておく
+			timer_cycle: match option.time_offset {
+				Some(offset) => time_cycle - offset,
+				None => 0,
+			},
+			cycle_cur: time_cycle,
 			timer_ready: 0,
+			ready_since: 0,
 			timer_run: 0,
+			timer_slice: 0,
+			timer_block: 0,
+			timer_evwait: 0,
+			overhead_remain: 0,
+			overhead_job: 0,
+			kernel_remain: 0,
+			kernel_exit: 0,
+			kernel_exit_done: false,
+			kernel_log: false,
+			job_started: false,
+			inherit_priority: None,
+			mutex_released: vec![],
+			blocked_by: None,
+			blocked_since: 0,
+			jitter_cur: 0,
+			rand: ProcessRand::new(0),
+			exec_rand: ProcessRand::new(0),
+			job_release: 0,
+			job_overrun: false,
+			act_queue: VecDeque::new(),
+			job_chain: vec![],
+			trigger_events: vec![],
+			job_done: None,
+			timer_blocked: 0,
+			timer_masked: 0,
 			log_cpu_time: 0,
 			log_cycle_delayed: false,
 			log_callback: cb,
 			// プロセス毎最大占有率
 			max_cpu_use_rate: 0.0,
 			max_cpu_time: 0,
+			mode_max_cpu_use_rate: 0.0,
+			cnt_overrun: 0,
+			cnt_deadline_miss: 0,
+			max_lateness: None,
+			cnt_act_queued: 0,
+			act_dropped: vec![],
+			cnt_exec: 0,
+			min_exec: i32::MAX,
+			max_exec: 0,
+			total_exec: 0,
+			max_blocked: 0,
+			total_blocked: 0,
+			cnt_inversion: 0,
+			max_inversion: 0,
+			cnt_migration: 0,
+			cnt_evwait: 0,
+			max_evwait: 0,
+			total_evwait: 0,
+			max_latency: 0,
+			max_masked: 0,
+			total_masked: 0,
 		}
 	}
 	// INTRプロセスファクトリ
-	pub fn intr(name: String, state: ProcessState, priority:i32, multi_intr:bool, time_cycle: i32, time_proc: Vec<i32>, cb: T) -> Process<T> {
-		Process{
-			id: -1,
-			kind: ProcessKind::INTR,
-			priority: priority,
-			multi_intr: multi_intr,
-			time_cycle: time_cycle,
-			time_proc: time_proc,
-			time_proc_idx: 0,
-			name: name,
-			state,
-			timer_cycle: 0,
-			timer_ready: 0,
-			timer_run: 0,
-			log_cpu_time: 0,
-			log_cycle_delayed: false,
-			log_callback: cb,
-			// プロセス毎最大占有率
-			max_cpu_use_rate: 0.0,
-			max_cpu_time: 0,
-		}
+	#[allow(clippy::too_many_arguments)]
+	pub fn intr(name: String, state: ProcessState, priority:i32, multi_intr:bool, time_cycle: i32, time_proc: Vec<i32>, option: ProcessOption, cb: T) -> Process<T> {
+		Process::new(ProcessKind::INTR, name, state, priority, multi_intr, time_cycle, time_proc, option, cb)
 	}
 	// TASKプロセスファクトリ
-	pub fn task(name: String, state: ProcessState, priority:i32, multi_intr:bool, time_cycle: i32, time_proc: Vec<i32>, cb: T) -> Process<T> {
-		Process{
-			id: -1,
-			kind: ProcessKind::TASK,
-			priority: priority,
-			multi_intr: multi_intr,
-			time_cycle: time_cycle,
-			time_proc: time_proc,
-			time_proc_idx: 0,
-			name: name,
-			state,
-			timer_cycle: 0,
-			timer_ready: 0,
-			timer_run: 0,
-			log_cpu_time: 0,
-			log_cycle_delayed: false,
-			log_callback: cb,
-			// プロセス毎最大占有率
-			max_cpu_use_rate: 0.0,
-			max_cpu_time: 0,
-		}
+	#[allow(clippy::too_many_arguments)]
+	pub fn task(name: String, state: ProcessState, priority:i32, multi_intr:bool, time_cycle: i32, time_proc: Vec<i32>, option: ProcessOption, cb: T) -> Process<T> {
+		Process::new(ProcessKind::TASK, name, state, priority, multi_intr, time_cycle, time_proc, option, cb)
 	}
 
 	pub fn go(&mut self, cpu_time:i32, elapse:i32) {
-		// 経過時間更新
-		self.timer_cycle += elapse;
-		// 起動周期チェック
-		self.check_cycle();
+		// 外部起動のプロセス、現在モードで起動しないプロセスは起動周期を使用しない
+		if !self.external && !self.suspended {
+			// 経過時間更新
+			self.timer_cycle += elapse;
+			// 起動周期チェック
+			self.check_cycle(cpu_time);
+		}
 		// 状態処理
 		self.check_state(cpu_time, elapse);
 	}
 
-	fn check_cycle(&mut self) {
+	/**
+	次イベントまでの時間
+	自プロセスに状態変化が発生するまでの経過時間を返す
+	*/
+	pub fn get_next_event(&self) -> i32 {
+		let remain = match self.state {
+			// WAITINGは起動周期+リリースジッタ到達で起床
+			// 外部起動のプロセスは起動元の状態変化で起床
+			ProcessState::WAITING => match self.external || self.suspended {
+				true => i32::MAX,
+				false => self.cycle_cur.saturating_add(self.jitter_cur).saturating_sub(self.timer_cycle),
+			},
+			// RUNNINGは処理時間経過で終了
+			// タイムスライス有効時はスライス満了、リソース使用時はロック/アンロックも対象
+			ProcessState::RUNNING => {
+				let mut remain = self.time_proc_cur - self.timer_run;
+				// オーバーヘッド消費中は処理時間が進まないため消費完了までとする
+				if self.overhead_remain > 0 {
+					remain = self.overhead_remain;
+				}
+				// カーネル処理はオーバーヘッドより先に消費する
+				if self.kernel_remain > 0 {
+					remain = self.kernel_remain;
+				}
+				let quantum = self.get_time_quantum();
+				if quantum > 0 && self.timer_slice < quantum {
+					remain = remain.min(quantum - self.timer_slice);
+				}
+				for section in self.critical_sections.iter() {
+					if let Some(point) = section.get_next_point(self.timer_run) {
+						remain = remain.min(point);
+					}
+				}
+				for section in self.mutex_sections.iter() {
+					if let Some(point) = section.get_next_point(self.timer_run) {
+						if point > 0 {
+							remain = remain.min(point);
+						}
+					}
+				}
+				for section in self.intlock_sections.iter() {
+					if let Some(point) = section.get_next_point(self.timer_run) {
+						remain = remain.min(point);
+					}
+				}
+				for point in self.sched_points.iter() {
+					if *point > self.timer_run {
+						remain = remain.min(*point - self.timer_run);
+					}
+				}
+				for (_, offset) in self.triggers.iter() {
+					if let Some(point) = offset {
+						if *point > self.timer_run {
+							remain = remain.min(*point - self.timer_run);
+						}
+					}
+				}
+				for point in self.sync_points.iter() {
+					if !point.done && point.offset > self.timer_run {
+						remain = remain.min(point.offset - self.timer_run);
+					}
+				}
+				remain
+			},
+			// READYはディスパッチされるまで変化なし
+			ProcessState::READY => i32::MAX,
+			// BLOCKEDはミューテックスを獲得するまで変化なし
+			ProcessState::BLOCKED => i32::MAX,
+			// EVWAITはデータ/フラグを受け取るまで変化なし
+			ProcessState::EVWAIT => i32::MAX,
+			// DORMANTは不使用
+			ProcessState::DORMANT => i32::MAX,
+		};
+		// 少なくとも1tickは進める
+		remain.max(1)
+	}
+
+	fn check_cycle(&mut self, cpu_time:i32) {
 		// 起動周期経過？
-		if self.timer_cycle >= self.time_cycle {
+		if self.timer_cycle >= self.cycle_cur {
 			// 状態毎処理
 			match self.state {
 				// WAITINGでは処理なし
 				ProcessState::WAITING => (),
 				// READY中に次の起動周期が来てしまったため、処理つぶれが発生している
-				ProcessState::READY => self.log_cycle_delayed = true,
+				ProcessState::READY => self.overrun(cpu_time),
 				// RUNNING中に次の起動周期が来てしまったため、処理つぶれが発生している
-				ProcessState::RUNNING => self.log_cycle_delayed = true,
+				ProcessState::RUNNING => self.overrun(cpu_time),
+				// BLOCKED中に次の起動周期が来てしまったため、処理つぶれが発生している
+				ProcessState::BLOCKED => self.overrun(cpu_time),
+				// EVWAIT中に次の起動周期が来てしまったため、処理つぶれが発生している
+				ProcessState::EVWAIT => self.overrun(cpu_time),
 				// DORMANTは不使用
 				ProcessState::DORMANT => (),
 			}
 		}
 	}
 
+	fn overrun(&mut self, cpu_time:i32) {
+		self.log_cycle_delayed = true;
+		// 処理つぶれはジョブ毎に1回カウント
+		if !self.job_overrun {
+			self.job_overrun = true;
+			self.cnt_overrun += 1;
+		}
+		// 多重起動上限が指定されていれば起動要求を記録
+		if let Some(limit) = self.act_limit {
+			self.check_activation(cpu_time, limit);
+		}
+	}
+
+	/**
+	多重起動要求チェック
+	READY/RUNNING中に到達した起動要求を上限までキューイングし、超過分は破棄する
+	起動周期タイマは到達毎に巻き戻して周期を維持する
+	*/
+	fn check_activation(&mut self, cpu_time:i32, limit:i32) {
+		// 1回の時間経過で複数回到達している場合もある
+		while self.cycle_cur > 0 && self.timer_cycle >= self.cycle_cur {
+			let release = cpu_time - (self.timer_cycle - self.cycle_cur);
+			self.queue_activation(release, vec![(self.id, release)], limit);
+			// 起動周期タイマ更新
+			self.timer_cycle -= self.cycle_cur;
+			self.jitter_cur = self.next_jitter();
+			self.cycle_cur = self.next_cycle(cpu_time, release);
+		}
+	}
+
+	/**
+	起動要求キューイング
+	実行中ジョブとキュー済みの起動要求で上限に達していなければキューイングし、超過分は破棄する
+	*/
+	fn queue_activation(&mut self, release:i32, job_chain: JobChain, limit:i32) {
+		if (self.act_queue.len() as i32) + 1 < limit {
+			self.act_queue.push_back((release, job_chain));
+			self.cnt_act_queued += 1;
+		} else {
+			self.act_dropped.push(release);
+		}
+	}
+
+	/**
+	起動トリガによる起動
+	job_chain: 起動元ジョブの起動元履歴
+	WAITING以外で起動された場合は処理つぶれとし、多重起動上限が指定されていればキューイングする
+	*/
+	pub fn activate(&mut self, cpu_time:i32, mut job_chain: JobChain) {
+		// 起動トリガが循環している場合は前回の自プロセス以降の履歴を破棄
+		if let Some(pos) = job_chain.iter().position(|(id, _)| *id == self.id) {
+			job_chain.truncate(pos);
+		}
+		job_chain.push((self.id, cpu_time));
+		if self.is_waiting() {
+			self.job_release = cpu_time;
+			self.job_overrun = false;
+			self.job_chain = job_chain;
+			self.wakeup(cpu_time);
+		} else {
+			self.log_cycle_delayed = true;
+			if !self.job_overrun {
+				self.job_overrun = true;
+				self.cnt_overrun += 1;
+			}
+			if let Some(limit) = self.act_limit {
+				self.queue_activation(cpu_time, job_chain, limit);
+			}
+		}
+	}
+
+	/**
+	外部起動設定
+	アラーム/スケジュールテーブルで起動する場合に設定する
+	*/
+	pub fn set_external(&mut self) {
+		self.external = true;
+	}
+
+	/**
+	起動トリガ登録
+	自プロセスのジョブ完了時(offset指定時はジョブ内処理時間到達時)にidxのプロセスを起動する
+	*/
+	pub fn add_trigger(&mut self, idx: usize, offset: Option<i32>) {
+		self.triggers.push((idx, offset));
+	}
+
+	/**
+	今回の時間経過で発生した起動トリガを取得
+	*/
+	pub fn take_trigger_events(&mut self) -> Vec<(usize, JobChain)> {
+		std::mem::take(&mut self.trigger_events)
+	}
+
+	/**
+	今回の時間経過で完了したジョブの起動元履歴を取得
+	*/
+	pub fn take_job_done(&mut self) -> Option<JobChain> {
+		self.job_done.take()
+	}
+
 	fn check_state(&mut self, cpu_time:i32, elapse:i32) {
 		// 状態毎処理
 		match self.state {
@@ -140,17 +467,44 @@ impl<T> Process<T>
 			ProcessState::READY => self.check_state_ready(elapse),
 			// RUNNING処理
 			ProcessState::RUNNING => self.check_state_running(cpu_time, elapse),
+			// BLOCKED処理
+			ProcessState::BLOCKED => self.check_state_blocked(elapse),
+			// EVWAIT処理
+			ProcessState::EVWAIT => self.check_state_evwait(elapse),
 			// DORMANTは不使用
 			ProcessState::DORMANT => self.check_state_dormant(),
 		}
 	}
 
 	fn check_state_waiting(&mut self, cpu_time:i32) {
-		if self.timer_cycle >= self.time_cycle {
+		if self.timer_cycle >= self.cycle_cur.saturating_add(self.jitter_cur) {
+			// 起動時刻は本来の起動周期到達時点とする
+			self.job_release = cpu_time - (self.timer_cycle - self.cycle_cur);
+			self.job_overrun = false;
+			self.job_chain = vec![(self.id, self.job_release)];
 			// 起動周期到達でタスク起床
 			self.wakeup(cpu_time);
 			// 起動周期タイマ初期化
-			self.timer_cycle = 0;
+			// ジッタ分遅れて起床しているため、その分を差し引いて周期を維持する
+			self.timer_cycle = self.jitter_cur;
+			self.jitter_cur = self.next_jitter();
+			self.cycle_cur = self.next_cycle(cpu_time, self.job_release);
+		}
+	}
+
+	fn next_cycle(&mut self, cpu_time:i32, last_release:i32) -> i32 {
+		// 起動周期タイマは初期化済みのため、タイマが0となる時刻を基準とする
+		let timer_base = cpu_time - self.timer_cycle;
+		match self.sporadic.as_mut() {
+			Some(sporadic) => sporadic.next_interval(last_release, timer_base, self.time_cycle, &mut self.rand),
+			None => self.time_cycle,
+		}
+	}
+
+	fn next_jitter(&mut self) -> i32 {
+		match self.release_jitter.as_mut() {
+			Some(jitter) => jitter.next(&mut self.rand),
+			None => 0,
 		}
 	}
 
@@ -160,24 +514,126 @@ impl<T> Process<T>
 		self.timer_ready += elapse;
 	}
 
+	fn check_state_blocked(&mut self, elapse:i32) {
+		// BLOCKEDは上位からミューテックスを渡されるまで待機
+		// 状態時間更新
+		self.timer_block += elapse;
+	}
+
+	fn check_state_evwait(&mut self, elapse:i32) {
+		// EVWAITは上位からデータ/フラグを渡されるまで待機
+		// 状態時間更新
+		self.timer_evwait += elapse;
+		self.total_evwait += elapse;
+	}
+
 	fn check_state_running(&mut self, cpu_time:i32, elapse:i32) {
+		let run_prev = self.timer_run;
+		// カーネル処理、オーバーヘッドの順に先に消費
+		// カーネル処理中はカーネル擬似プロセスが実行しているため、自プロセスは実行待ちとする
+		let kernel = self.kernel_remain.min(elapse);
+		self.kernel_remain -= kernel;
+		self.timer_ready += kernel;
+		let overhead = self.overhead_remain.min(elapse - kernel);
+		self.overhead_remain -= overhead;
+		self.overhead_job += overhead;
 		// 状態時間更新
-		self.timer_run += elapse;
+		self.timer_run += elapse - kernel - overhead;
+		self.timer_slice += elapse;
+		// ジョブ内処理時間による起動トリガ
+		for (idx, offset) in self.triggers.iter() {
+			if let Some(point) = offset {
+				if run_prev < *point && *point <= self.timer_run {
+					self.trigger_events.push((*idx, self.job_chain.clone()));
+				}
+			}
+		}
 		// 処理時間経過判定
-		if self.timer_run >= self.time_proc[self.time_proc_idx] {
+		if self.timer_run >= self.time_proc_cur {
+			// ISR出口のカーネル処理が完了するまでジョブ完了を待つ
+			if !self.kernel_exit_done {
+				self.kernel_exit_done = true;
+				self.kernel_remain += self.kernel_exit;
+			}
+			if self.kernel_remain > 0 {
+				self.check_kernel_log(cpu_time);
+				return;
+			}
 			// RUNNING終了してWAITINGへ
 			// 処理時間idx更新
 			self.time_proc_idx += 1;
 			if self.time_proc_idx >= self.time_proc.len() {
 				self.time_proc_idx = 0;
 			}
+			// 処理時間集計
+			self.cnt_exec += 1;
+			self.min_exec = self.min_exec.min(self.time_proc_cur);
+			self.max_exec = self.max_exec.max(self.time_proc_cur);
+			self.total_exec += self.time_proc_cur as i64;
+			self.next_exec_time();
 			// 占有率計算
 			self.calc_cpu_usage(cpu_time);
-			// 状態遷移
-			self.waiting(cpu_time);
-			//
-			self.timer_run = 0;
-			self.timer_ready = 0;
+			// デッドライン判定
+			self.check_deadline(cpu_time);
+			// ブロッキング時間集計
+			self.max_blocked = self.max_blocked.max(self.timer_blocked);
+			self.timer_blocked = 0;
+			// 割り込みマスク待ち時間集計
+			self.max_masked = self.max_masked.max(self.timer_masked);
+			self.timer_masked = 0;
+			// 占有中のミューテックスを解放
+			self.release_mutex_all();
+			self.timer_block = 0;
+			// 同期待ち時間集計
+			self.max_evwait = self.max_evwait.max(self.timer_evwait);
+			self.timer_evwait = 0;
+			for point in self.sync_points.iter_mut() {
+				point.done = false;
+			}
+			self.overhead_job = 0;
+			self.kernel_exit_done = false;
+			self.job_started = false;
+			// ジョブ完了による起動トリガ
+			for (idx, offset) in self.triggers.iter() {
+				if offset.is_none() {
+					self.trigger_events.push((*idx, self.job_chain.clone()));
+				}
+			}
+			self.job_done = Some(std::mem::take(&mut self.job_chain));
+			match self.act_queue.pop_front() {
+				Some((release, job_chain)) => {
+					// キューイングされた起動要求があれば続けて次のジョブを開始
+					self.job_release = release;
+					self.job_chain = job_chain;
+					self.job_overrun = false;
+					self.wakeup(cpu_time);
+					// 起動要求発生から待たされていた時間はREADY時間とする
+					self.timer_run = 0;
+					self.timer_ready = cpu_time - release;
+				},
+				None => {
+					// 状態遷移
+					self.waiting(cpu_time);
+					//
+					self.timer_run = 0;
+					self.timer_ready = 0;
+				}
+			}
+		} else {
+			// カーネル処理が完了していれば次のtickからRUNNINGとしてログ出力
+			self.check_kernel_log(cpu_time + 1);
+		}
+	}
+
+	/**
+	カーネル処理中のログ切り替え
+	カーネル処理中はカーネル擬似プロセスが実行しているため、自プロセスはREADYとしてログ出力する
+	*/
+	fn check_kernel_log(&mut self, cpu_time:i32) {
+		let kernel = self.kernel_remain > 0;
+		if kernel != self.kernel_log {
+			self.push_log(cpu_time);
+			self.kernel_log = kernel;
 		}
 	}
 
@@ -187,7 +643,7 @@ impl<T> Process<T>
 
 	fn calc_cpu_usage(&mut self, cpu_time:i32) {
 		// プロセスが有効になっていた時間
-		let active_time = self.timer_run + self.timer_ready;
+		let active_time = self.timer_run + self.timer_ready + self.timer_block + self.timer_evwait + self.overhead_job;
 		// 起動周期に占める割合＝CPU占有率
 		let userate: f32 = active_time as f32 / self.time_cycle as f32 * 100.0;
 		// 最大CPU占有率を覚えておく
@@ -195,6 +651,108 @@ impl<T> Process<T>
 			self.max_cpu_use_rate = userate;
 			self.max_cpu_time = cpu_time;
 		}
+		self.mode_max_cpu_use_rate = self.mode_max_cpu_use_rate.max(userate);
+	}
+
+	/**
+	絶対デッドライン
+	ジョブ起動時刻に相対デッドラインを加えた時刻を返す
+	*/
+	pub fn get_abs_deadline(&self) -> i32 {
+		self.job_release.saturating_add(self.time_deadline)
+	}
+
+	fn check_deadline(&mut self, cpu_time:i32) {
+		// 遅れ時間:負値はデッドラインまでの余裕
+		let lateness = cpu_time - self.get_abs_deadline();
+		if lateness > 0 {
+			self.cnt_deadline_miss += 1;
+		}
+		// 最大遅れ時間を覚えておく
+		match self.max_lateness {
+			Some(max) if max >= lateness => (),
+			_ => self.max_lateness = Some(lateness),
+		}
+	}
+
+	/**
+	乱数生成器初期化
+	*/
+	pub fn init_random(&mut self, seed: u64) {
+		self.rand = ProcessRand::new(seed);
+		// 処理時間はジッタ/到着間隔と独立した系列とする
+		self.exec_rand = ProcessRand::new(seed ^ 0x5EED_0000_0000_0000);
+	}
+
+	/**
+	次のジョブの処理時間を決定
+	処理時間分布が未指定であれば処理時間[Max,Ave1,Ave2,...]を順番に使用する
+	*/
+	fn next_exec_time(&mut self) {
+		self.time_proc_raw = match self.exec_time.as_mut() {
+			Some(dist) => dist.sample(self.time_proc[0], &mut self.exec_rand),
+			None => self.time_proc[self.time_proc_idx],
+		};
+		self.time_proc_cur = self.scale_time(self.time_proc_raw, self.ref_freq.unwrap_or(self.cpu_freq));
+	}
+
+	/**
+	周波数スケーリング
+	周波数freqでの時間timeを現在のCPU周波数での時間に換算する(切り上げ)
+	*/
+	fn scale_time(&self, time: i32, freq: i32) -> i32 {
+		if self.cpu_freq <= 0 || freq == self.cpu_freq {
+			return time;
+		}
+		((time as i64 * freq as i64 + self.cpu_freq as i64 - 1) / self.cpu_freq as i64) as i32
+	}
+
+	pub fn has_ref_freq(&self) -> bool {
+		self.ref_freq.is_some()
+	}
+
+	/**
+	周波数初期設定
+	基準周波数の個別設定が無ければ[TraceInfo]のRefFreqを使用する
+	*/
+	pub fn init_freq(&mut self, ref_freq: i32, cpu_freq: i32) {
+		if self.ref_freq.is_none() {
+			self.ref_freq = Some(ref_freq);
+		}
+		self.cpu_freq = cpu_freq;
+	}
+
+	/**
+	CPU周波数変更
+	未起動のジョブは処理時間を換算し直し、実行中のジョブは残り処理時間を換算する
+	*/
+	pub fn set_cpu_freq(&mut self, freq: i32) {
+		let prev = self.cpu_freq;
+		self.cpu_freq = freq;
+		if self.is_waiting() {
+			self.time_proc_cur = self.scale_time(self.time_proc_raw, self.ref_freq.unwrap_or(freq));
+		} else {
+			let remain = self.time_proc_cur - self.timer_run;
+			self.time_proc_cur = self.timer_run + self.scale_time(remain, prev);
+		}
+	}
+
+	/**
+	トレース開始処理
+	*/
+	pub fn start(&mut self) {
+		// 初期状態READYのジョブは時刻0の起動とする
+		if self.is_ready() {
+			self.job_chain = vec![(self.id, 0)];
+		}
+		// 初回ジョブの処理時間
+		self.next_exec_time();
+		// 初回起動のリリースジッタ
+		self.jitter_cur = self.next_jitter();
+		// 初回起動までの間隔
+		if let Some(sporadic) = self.sporadic.as_mut() {
+			self.cycle_cur = sporadic.first_interval(-self.timer_cycle, self.time_cycle, &mut self.rand);
+		}
 	}
 
 	pub fn init(&mut self, cpu_time:i32) {
@@ -207,6 +765,7 @@ impl<T> Process<T>
 		self.push_log(cpu_time);
 		// READYに遷移
 		self.state = ProcessState::READY;
+		self.ready_since = cpu_time;
 	}
 
 	pub fn waiting(&mut self, cpu_time:i32) {
@@ -218,42 +777,544 @@ impl<T> Process<T>
 
 	pub fn dispatch(&mut self, cpu_time:i32) {
 		// ログ登録
-		self.push_log(cpu_time);
+		// カーネル処理から開始する場合は完了までREADYのログを継続する
+		if !(self.is_ready() && self.kernel_remain > 0) {
+			self.push_log(cpu_time);
+		}
+		self.kernel_log = self.kernel_remain > 0;
+		// 割り込みレイテンシ計測:ジョブ開始時のみ
+		if !self.job_started {
+			if let ProcessKind::INTR = self.kind {
+				self.max_latency = self.max_latency.max(cpu_time - self.job_release);
+			}
+		}
+		// タイムスライス開始
+		self.timer_slice = 0;
+		self.job_started = true;
 		// RUNNINGに遷移
 		self.state = ProcessState::RUNNING;
 	}
 
 	pub fn preempt(&mut self, cpu_time:i32) {
 		// ログ登録
-		self.push_log(cpu_time);
+		// カーネル処理中であればREADYのログを継続する
+		if !self.kernel_log {
+			self.push_log(cpu_time);
+		}
+		self.kernel_log = false;
 		// READYに遷移
 		self.state = ProcessState::READY;
+		self.ready_since = cpu_time;
 	}
 
-	pub fn is_waiting(&mut self) -> bool {
-		if let ProcessState::WAITING = self.state {
-			true
-		} else {
-			false
+	/**
+	オーバーヘッド追加
+	RUNNING中に指定時間を消費するまで処理時間を進めない
+	*/
+	pub fn add_overhead(&mut self, time:i32) {
+		self.overhead_remain += time;
+	}
+
+	/**
+	カーネル処理追加
+	RUNNING中に指定時間を消費するまで処理時間を進めない
+	*/
+	pub fn add_kernel(&mut self, time:i32) {
+		self.kernel_remain += time;
+	}
+
+	/**
+	ジョブ完了時のカーネル処理時間設定(ISR出口)
+	*/
+	pub fn set_kernel_exit(&mut self, time:i32) {
+		self.kernel_exit = time;
+	}
+
+	/**
+	カーネル処理中判定
+	*/
+	pub fn is_kernel_running(&self) -> bool {
+		self.is_running() && self.kernel_remain > 0
+	}
+
+	/**
+	実行中ジョブがディスパッチ済みか判定
+	*/
+	pub fn is_job_started(&self) -> bool {
+		self.job_started
+	}
+
+	/**
+	実行コア変更
+	実行途中のジョブが前回と異なるコアにディスパッチされたらマイグレーションとしてコストを加算する
+	*/
+	pub fn migrate(&mut self, core: usize, cost:i32) {
+		if self.core != core && self.timer_run + self.overhead_job > 0 {
+			self.cnt_migration += 1;
+			self.add_overhead(cost);
 		}
+		self.core = core;
 	}
 
-	pub fn is_ready(&mut self) -> bool {
-		if let ProcessState::READY = self.state {
-			true
-		} else {
-			false
+	pub fn get_migration_result(&self) -> String {
+		format!("[{:40}] | {:9}", &self.name, &self.cnt_migration)
+	}
+
+	/**
+	現在の優先度
+	占有中リソースの優先度上限が自プロセスの優先度より高ければ引き上げる
+	*/
+	pub fn get_cur_priority(&self) -> i32 {
+		let mut priority = self.priority;
+		// 優先度継承
+		if let Some(inherit) = self.inherit_priority {
+			priority = priority.max(inherit);
+		}
+		// ジョブ開始前(処理時間0)のREADYはまだロックしていない
+		if self.is_running() || self.timer_run > 0 {
+			for section in self.critical_sections.iter() {
+				if section.is_locked(self.timer_run) {
+					priority = priority.max(section.ceiling);
+				}
+			}
+		}
+		priority
+	}
+
+	/**
+	TASK間の比較に用いる優先度
+	プリエンプション閾値:ディスパッチ済みのTASKは閾値まで引き上げ
+	スケジュールポイントでは閾値を解除する
+	INTRとの比較には閾値を適用しない
+	*/
+	pub fn get_task_priority(&self) -> i32 {
+		let priority = self.get_cur_priority();
+		if let (ProcessKind::TASK, Some(threshold)) = (&self.kind, self.threshold) {
+			if self.job_started && !self.is_sched_point() {
+				return priority.max(threshold);
+			}
+		}
+		priority
+	}
+
+	/**
+	プリエンプション閾値の既定値設定
+	閾値未指定のTASKはpreemptiveであれば優先度、non-preemptiveであれば最大値とする
+	*/
+	pub fn set_default_threshold(&mut self, task_use_preempt: bool) {
+		if let (ProcessKind::TASK, None) = (&self.kind, self.threshold) {
+			self.threshold = Some(match task_use_preempt {
+				true => self.priority,
+				false => i32::MAX,
+			});
+		}
+	}
+
+	pub fn has_threshold(&self) -> bool {
+		matches!((&self.kind, self.threshold), (ProcessKind::TASK, Some(_)))
+	}
+
+	/**
+	スケジュールポイント判定
+	RUNNING中に処理時間がスケジュールポイントに達していればtrueを返す
+	*/
+	pub fn is_sched_point(&self) -> bool {
+		self.is_running() && self.timer_run > 0 && self.sched_points.contains(&self.timer_run)
+	}
+
+	/**
+	割り込みレベル
+	優先度とは別の尺度のため、未指定時は優先度で代用せずNoneとする
+	*/
+	pub fn get_hw_level(&self) -> Option<i32> {
+		self.level
+	}
+
+	/**
+	割り込みレベル指定有無
+	*/
+	pub fn has_hw_level(&self) -> bool {
+		self.level.is_some()
+	}
+
+	pub fn has_intlock(&self) -> bool {
+		!self.intlock_sections.is_empty()
+	}
+
+	/**
+	マスクレベル指定の割り込み禁止区間有無
+	*/
+	pub fn has_intlock_level(&self) -> bool {
+		self.intlock_sections.iter().any(|section| section.level != i32::MAX)
+	}
+
+	/**
+	割り込みマスクレベル
+	割り込み禁止区間を処理中であればマスクしている割り込みレベル上限を返す
+	*/
+	pub fn get_mask_level(&self) -> Option<i32> {
+		let mut result: Option<i32> = None;
+		// ジョブ開始前(処理時間0)のREADYはまだ禁止していない
+		if self.is_running() || self.timer_run > 0 {
+			for section in self.intlock_sections.iter() {
+				if section.is_locked(self.timer_run) {
+					result = Some(result.map_or(section.level, |level| level.max(section.level)));
+				}
+			}
+		}
+		result
+	}
+
+	/**
+	割り込みマスク待ち時間加算
+	*/
+	pub fn add_masked_time(&mut self, elapse:i32) {
+		self.timer_masked += elapse;
+		self.total_masked += elapse;
+	}
+
+	pub fn get_latency_result(&self) -> Option<String> {
+		match self.kind {
+			ProcessKind::INTR => Some(format!("[{:40}] | {:>5} | {:11} | {:10} | {:12}", &self.name, self.level.map_or("-".to_string(), |level| level.to_string()), &self.max_latency, self.max_masked.max(self.timer_masked), &self.total_masked)),
+			ProcessKind::TASK => None,
+		}
+	}
+
+	pub fn get_critical_sections(&self) -> &Vec<CriticalSection> {
+		&self.critical_sections
+	}
+
+	/**
+	リソース毎の優先度上限を設定
+	*/
+	pub fn set_resource_ceiling(&mut self, ceilings: &HashMap<String,i32>) {
+		for section in self.critical_sections.iter_mut() {
+			if let Some(ceiling) = ceilings.get(&section.resource) {
+				section.ceiling = *ceiling;
+			}
+		}
+	}
+
+	pub fn set_inherit_priority(&mut self, priority: Option<i32>) {
+		self.inherit_priority = priority;
+	}
+
+	pub fn has_mutex(&self) -> bool {
+		!self.mutex_sections.is_empty()
+	}
+
+	pub fn get_mutex_names(&self) -> Vec<String> {
+		self.mutex_sections.iter().map(|section| section.mutex.clone()).collect()
+	}
+
+	pub fn get_mutex_name(&self, idx: usize) -> &String {
+		&self.mutex_sections[idx].mutex
+	}
+
+	/**
+	ミューテックス操作要求
+	現在の処理時間でアンロック/ロックすべき占有区間があれば返す
+	*/
+	pub fn get_mutex_request(&self) -> Option<MutexRequest> {
+		for (idx, section) in self.mutex_sections.iter().enumerate() {
+			if section.locked && self.timer_run >= section.offset + section.length {
+				return Some(MutexRequest::UNLOCK(idx));
+			}
+		}
+		if self.is_running() {
+			if let Some(idx) = self.get_lock_pending() {
+				return Some(MutexRequest::LOCK(idx));
+			}
+		}
+		None
+	}
+
+	/**
+	ロック要求中の占有区間
+	*/
+	pub fn get_lock_pending(&self) -> Option<usize> {
+		self.mutex_sections.iter().position(|section| {
+			!section.locked && !section.done && self.timer_run >= section.offset
+		})
+	}
+
+	pub fn lock_mutex(&mut self, idx: usize) {
+		self.mutex_sections[idx].locked = true;
+	}
+
+	pub fn unlock_mutex(&mut self, idx: usize) {
+		self.mutex_sections[idx].locked = false;
+		self.mutex_sections[idx].done = true;
+	}
+
+	/**
+	ジョブ完了時に占有中のミューテックスを解放
+	解放したミューテックスは上位で待機プロセスへ引き渡す
+	*/
+	fn release_mutex_all(&mut self) {
+		for section in self.mutex_sections.iter_mut() {
+			if section.locked {
+				self.mutex_released.push(section.mutex.clone());
+			}
+			section.locked = false;
+			section.done = false;
 		}
 	}
 
-	pub fn is_running(&mut self) -> bool {
-		if let ProcessState::RUNNING = self.state {
-			true
+	pub fn take_released_mutexes(&mut self) -> Vec<String> {
+		std::mem::take(&mut self.mutex_released)
+	}
+
+	pub fn has_sync(&self) -> bool {
+		!self.sync_points.is_empty()
+	}
+
+	pub fn get_sync_points(&self) -> &Vec<SyncPoint> {
+		&self.sync_points
+	}
+
+	/**
+	同期操作要求
+	RUNNING中に現在の処理時間で操作すべき同期ポイントがあれば返す
+	*/
+	pub fn get_sync_request(&self) -> Option<usize> {
+		if self.is_running() {
+			self.get_sync_pending()
 		} else {
-			false
+			None
 		}
 	}
 
+	/**
+	操作待ちの同期ポイント
+	*/
+	pub fn get_sync_pending(&self) -> Option<usize> {
+		self.sync_points.iter().position(|point| !point.done && self.timer_run >= point.offset)
+	}
+
+	pub fn done_sync(&mut self, idx: usize) {
+		self.sync_points[idx].done = true;
+	}
+
+	/**
+	データ/フラグ待ち
+	*/
+	pub fn wait_event(&mut self, cpu_time:i32) {
+		// ログ登録
+		self.push_log(cpu_time);
+		// EVWAITに遷移
+		self.state = ProcessState::EVWAIT;
+		self.cnt_evwait += 1;
+	}
+
+	/**
+	データ/フラグ受け取りによりEVWAITから復帰
+	*/
+	pub fn release_event(&mut self, cpu_time:i32) {
+		if let Some(idx) = self.get_sync_pending() {
+			self.done_sync(idx);
+		}
+		self.wakeup(cpu_time);
+	}
+
+	pub fn get_evwait_result(&self) -> String {
+		format!("[{:40}] | {:7} | {:10} | {:10}", &self.name, &self.cnt_evwait, self.max_evwait.max(self.timer_evwait), &self.total_evwait)
+	}
+
+	/**
+	ミューテックス待ち
+	blockerは優先度逆転を起こしているプロセスID
+	*/
+	pub fn block(&mut self, cpu_time:i32, blocker: Option<i32>) {
+		// ログ登録
+		self.push_log(cpu_time);
+		// BLOCKEDに遷移
+		self.state = ProcessState::BLOCKED;
+		self.set_blocker(cpu_time, blocker);
+	}
+
+	/**
+	ミューテックス獲得によりBLOCKEDから復帰
+	*/
+	pub fn unblock(&mut self, cpu_time:i32) {
+		self.set_blocker(cpu_time, None);
+		self.wakeup(cpu_time);
+	}
+
+	/**
+	ブロック元プロセス更新
+	それまでの優先度逆転区間をログとして通知する
+	*/
+	pub fn set_blocker(&mut self, cpu_time:i32, blocker: Option<i32>) {
+		if let Some(blocked_by) = self.blocked_by {
+			let time = cpu_time - self.blocked_since;
+			// 優先度逆転を通知
+			(self.log_callback)(
+				&self.name,
+				self.id,
+				ProcessLog::INVERSION(blocked_by),
+				self.blocked_since,
+				cpu_time,
+				false,
+			);
+			self.cnt_inversion += 1;
+			self.max_inversion = self.max_inversion.max(time);
+			self.add_blocked_time(time);
+		}
+		self.blocked_by = blocker;
+		self.blocked_since = cpu_time;
+	}
+
+	/**
+	ブロッキング時間加算
+	*/
+	pub fn add_blocked_time(&mut self, elapse:i32) {
+		self.timer_blocked += elapse;
+		self.total_blocked += elapse;
+	}
+
+	/**
+	ブロッキング結果出力
+	*/
+	pub fn get_blocking_result(&self) -> String {
+		format!("[{:40}] | {:10} | {:11} | {:9} | {:10}", &self.name, self.max_blocked.max(self.timer_blocked), &self.total_blocked, &self.cnt_inversion, &self.max_inversion)
+	}
+
+	/**
+	タイムクォンタム未指定であれば既定値を設定する
+	ラウンドロビンはTASKのみ対象
+	*/
+	pub fn set_default_time_quantum(&mut self, quantum: i32) {
+		if self.time_quantum.is_none() {
+			self.time_quantum = Some(quantum);
+		}
+	}
+
+	pub fn get_time_quantum(&self) -> i32 {
+		match self.kind {
+			ProcessKind::TASK => self.time_quantum.unwrap_or(0),
+			ProcessKind::INTR => 0,
+		}
+	}
+
+	/**
+	タイムスライス満了判定
+	*/
+	pub fn is_slice_expired(&self) -> bool {
+		let quantum = self.get_time_quantum();
+		quantum > 0 && self.timer_slice >= quantum
+	}
+
+	/**
+	タイムスライス再開
+	満了時に交代先が無ければ次のスライスを開始する
+	*/
+	pub fn restart_slice(&mut self) {
+		self.timer_slice = 0;
+	}
+
+	/**
+	多重起動結果出力
+	多重起動上限が指定されていなければNone
+	*/
+	pub fn get_activation_result(&self) -> Option<String> {
+		self.act_limit?;
+		// 破棄時刻は先頭から一部のみ出力
+		const DISP_MAX: usize = 10;
+		let mut dropped: Vec<String> = self.act_dropped.iter().take(DISP_MAX).map(|time| time.to_string()).collect();
+		if self.act_dropped.len() > DISP_MAX {
+			dropped.push("...".to_string());
+		}
+		Some(format!("[{:40}] | {:7} | {:7} | {}", &self.name, &self.cnt_act_queued, self.act_dropped.len(), dropped.join(", ")))
+	}
+
+	/**
+	処理時間分布結果出力
+	処理時間分布が指定されていなければNone
+	*/
+	pub fn get_exec_result(&self) -> Option<String> {
+		let dist = self.exec_time.as_ref()?;
+		let (min, ave) = match self.cnt_exec {
+			0 => ("-".to_string(), "-".to_string()),
+			_ => (self.min_exec.to_string(), format!("{:.2}", self.total_exec as f64 / self.cnt_exec as f64)),
+		};
+		Some(format!("[{:40}] | {:7} | {:7} | {:>9} | {:>9} | {:>9}", &self.name, dist.mode.to_string(), &self.cnt_exec, min, ave, self.max_exec))
+	}
+
+	/**
+	モード別パラメータを指定しているモード名
+	*/
+	pub fn get_mode_names(&self) -> Vec<&String> {
+		self.modes.keys().collect()
+	}
+
+	/**
+	モード切り替え
+	モード別パラメータが無ければ基本設定に戻す
+	実行中のジョブは切り替え前の処理時間で完了し、次のジョブから適用する
+	*/
+	pub fn change_mode(&mut self, cpu_time:i32, mode: &str) {
+		let param = self.modes.get(mode).cloned().unwrap_or_default();
+		let cycle = param.cycle.unwrap_or(self.base_cycle);
+		let active = param.active.unwrap_or(true);
+		let time_proc = param.time_proc.unwrap_or_else(|| self.base_time_proc.clone());
+		// 起動周期の変更、起動の再開は切り替え時点から周期を開始する
+		if cycle != self.time_cycle || (active && self.suspended) {
+			self.time_cycle = cycle;
+			if self.implicit_deadline {
+				self.time_deadline = cycle;
+			}
+			self.timer_cycle = 0;
+			self.cycle_cur = self.next_cycle(cpu_time, cpu_time);
+		}
+		self.suspended = !active;
+		if time_proc != self.time_proc {
+			self.time_proc = time_proc;
+			self.time_proc_idx = 0;
+			// 未起動であれば次のジョブの処理時間を選び直す
+			if self.is_waiting() {
+				self.next_exec_time();
+			}
+		}
+	}
+
+	/**
+	モード別集計用: 現在モードでの最大占有率を取得してクリア
+	*/
+	pub fn take_mode_max_cpu_use_rate(&mut self) -> f32 {
+		std::mem::take(&mut self.mode_max_cpu_use_rate)
+	}
+
+	/**
+	モンテカルロ集計用: 最大占有率
+	*/
+	pub fn get_max_cpu_use_rate(&self) -> f32 {
+		self.max_cpu_use_rate
+	}
+
+	/**
+	モンテカルロ集計用: 処理つぶれ回数, デッドラインミス回数, 最大遅れ時間
+	*/
+	pub fn get_delay_count(&self) -> (i32, i32, Option<i32>) {
+		(self.cnt_overrun, self.cnt_deadline_miss, self.max_lateness)
+	}
+
+	pub fn is_waiting(&self) -> bool {
+		matches!(self.state, ProcessState::WAITING)
+	}
+
+	pub fn is_ready(&self) -> bool {
+		matches!(self.state, ProcessState::READY)
+	}
+
+	pub fn is_blocked(&self) -> bool {
+		matches!(self.state, ProcessState::BLOCKED)
+	}
+
+	pub fn is_running(&self) -> bool {
+		matches!(self.state, ProcessState::RUNNING)
+	}
+
 
 	/**
 	ログ登録
@@ -261,10 +1322,15 @@ impl<T> Process<T>
 	*/
 	fn push_log(&mut self, cpu_time:i32) {
 		// ログを通知
+		// カーネル処理中はREADYとして通知する
+		let state = match self.kernel_log {
+			true => ProcessState::READY,
+			false => self.state,
+		};
 		(self.log_callback)(
 			&self.name,
 			self.id,
-			self.state,
+			ProcessLog::STATE(state),
 			self.log_cpu_time,
 			cpu_time,
 			self.log_cycle_delayed,
@@ -272,6 +1338,7 @@ impl<T> Process<T>
 		// ログクリア
 		self.log_cpu_time = cpu_time;
 		self.log_cycle_delayed = false;
+		self.kernel_log = false;
 	}
 }
 
@@ -283,6 +1350,10 @@ impl<T> std::fmt::Display for Process<T>
 		if self.max_cpu_use_rate > 100.0 {
 			delay = "(delayed!)".to_string();
 		}
-		write!(f, "[{:40}] | {:10.2}% (at {:10}) {}", &self.name, &self.max_cpu_use_rate, &self.max_cpu_time, delay)
+		let lateness = match self.max_lateness {
+			Some(lateness) => lateness.to_string(),
+			None => "-".to_string(),
+		};
+		write!(f, "[{:40}] | {:10.2}% (at {:10}) | {:7} | {:7} | {:>10} {}", &self.name, &self.max_cpu_use_rate, &self.max_cpu_time, &self.cnt_overrun, &self.cnt_deadline_miss, lateness, delay)
 	}
 }
@@ -0,0 +1,121 @@
+/// 起動テーブル種類
+#[derive(Clone, Copy)]
+pub enum AlarmKind {
+	/// アラーム(単発/周期)
+	ALARM,
+	/// スケジュールテーブル
+	SCHEDTABLE,
+}
+impl std::fmt::Display for AlarmKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			AlarmKind::ALARM => write!(f,"ALARM"),
+			AlarmKind::SCHEDTABLE => write!(f,"SCHEDTABLE"),
+		}
+	}
+}
+
+/// 満了時の処理
+#[derive(Clone)]
+pub enum AlarmAction {
+	/// プロセス起動(プロセスidx)
+	ACTIVATE(usize),
+	/// イベントセット(イベント名 TASK.EVENT)
+	SETEVENT(String),
+}
+
+/**
+起動テーブル
+アラーム/スケジュールテーブルを満了点(基準時刻からのオフセット, 起動プロセス)の並びとして扱う
+アラームは満了点1つのテーブル、周期0は単発として扱う
+時間はカウンタのtickから換算済みのCPU時間(us)で保持する
+*/
+#[derive(Clone)]
+pub struct ActivationTable {
+	pub name: String,						// 名称
+	pub kind: AlarmKind,					// 種類
+	period: i32,							// 繰り返し周期, 0で単発
+	points: Vec<(i32, Vec<String>)>,		// 満了点(オフセット, 起動プロセス名/イベント名)
+	targets: Vec<Vec<AlarmAction>>,			// 満了点毎の処理
+	// 制御データ
+	base: i32,								// 今回周回の基準時刻
+	point_idx: usize,						// 次の満了点idx
+	finished: bool,							// 単発の満了完了
+	// 統計
+	cnt_expiry: i32,						// 満了回数
+	cnt_activate: i32,						// 起動要求数
+}
+
+impl ActivationTable {
+	pub fn new(name: String, kind: AlarmKind, start: i32, period: i32, mut points: Vec<(i32, Vec<String>)>) -> ActivationTable {
+		points.sort_by_key(|(offset, _)| *offset);
+		ActivationTable {
+			name,
+			kind,
+			period,
+			finished: points.is_empty(),
+			points,
+			targets: vec![],
+			base: start,
+			point_idx: 0,
+			cnt_expiry: 0,
+			cnt_activate: 0,
+		}
+	}
+
+	/**
+	満了点毎の起動プロセス名/イベント名
+	*/
+	pub fn get_target_names(&self) -> Vec<&Vec<String>> {
+		self.points.iter().map(|(_, names)| names).collect()
+	}
+
+	/**
+	満了点毎の処理設定
+	*/
+	pub fn set_targets(&mut self, targets: Vec<Vec<AlarmAction>>) {
+		self.targets = targets;
+	}
+
+	/**
+	次の満了時刻
+	*/
+	pub fn get_next_expiry(&self) -> Option<i32> {
+		if self.finished {
+			None
+		} else {
+			Some(self.base.saturating_add(self.points[self.point_idx].0))
+		}
+	}
+
+	/**
+	満了チェック
+	cpu_timeまでに満了した満了点の処理を返す
+	*/
+	pub fn expire(&mut self, cpu_time: i32) -> Vec<AlarmAction> {
+		let mut result = vec![];
+		while let Some(expiry) = self.get_next_expiry() {
+			if expiry > cpu_time {
+				break;
+			}
+			result.extend(self.targets[self.point_idx].iter().cloned());
+			self.cnt_expiry += 1;
+			// 次の満了点へ
+			self.point_idx += 1;
+			if self.point_idx >= self.points.len() {
+				self.point_idx = 0;
+				if self.period > 0 {
+					self.base = self.base.saturating_add(self.period);
+				} else {
+					self.finished = true;
+				}
+			}
+		}
+		self.cnt_activate += result.len() as i32;
+		result
+	}
+
+	pub fn get_result(&self) -> String {
+		format!("[{:40}] | {:10} | {:9} | {:9}", &self.name, self.kind.to_string(), &self.cnt_expiry, &self.cnt_activate)
+	}
+}
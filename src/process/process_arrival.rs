@@ -0,0 +1,77 @@
+use crate::process::process_rand::ProcessRand;
+
+/// スポラディック起動の到着パターン
+#[derive(Clone, Copy, PartialEq)]
+pub enum ArrivalMode {
+	/// 最小到着間隔で到着し続ける(最悪ケース)
+	DENSE,
+	/// [最小到着間隔,最大到着間隔]の一様乱数間隔で到着
+	RANDOM,
+	/// 指定した到着時刻リストに従って到着
+	LIST,
+}
+
+/**
+スポラディック起動
+最小到着間隔(起動周期欄)以上の間隔で到着パターンに従って起動する
+*/
+#[derive(Clone)]
+pub struct SporadicArrival {
+	mode: ArrivalMode,
+	max_interval: i32,		// 最大到着間隔(RANDOM)
+	arrivals: Vec<i32>,		// 到着時刻リスト(LIST)
+	idx: usize,				// 次の到着時刻idx(LIST)
+}
+
+impl SporadicArrival {
+	pub fn new(mode: ArrivalMode, max_interval: i32, arrivals: Vec<i32>) -> SporadicArrival {
+		SporadicArrival {
+			mode,
+			max_interval,
+			arrivals,
+			idx: 0,
+		}
+	}
+
+	/**
+	トレース開始から初回到着までの時間
+	timer_base: 起動周期タイマが0となる時刻
+	*/
+	pub fn first_interval(&mut self, timer_base: i32, min_interval: i32, rand: &mut ProcessRand) -> i32 {
+		self.idx = 0;
+		match self.mode {
+			ArrivalMode::LIST => self.next_list(None, timer_base, min_interval),
+			_ => self.next_interval(0, timer_base, min_interval, rand),
+		}
+	}
+
+	/**
+	次回到着までの起動周期タイマ値
+	DENSE/RANDOMは前回到着からの間隔、LISTは到着時刻リストの時刻までの時間とする
+	timer_base: 起動周期タイマが0となる時刻
+	*/
+	pub fn next_interval(&mut self, last_arrival: i32, timer_base: i32, min_interval: i32, rand: &mut ProcessRand) -> i32 {
+		match self.mode {
+			ArrivalMode::DENSE => min_interval,
+			ArrivalMode::RANDOM => rand.range(min_interval, self.max_interval.max(min_interval)),
+			ArrivalMode::LIST => self.next_list(Some(last_arrival), timer_base, min_interval),
+		}
+	}
+
+	fn next_list(&mut self, last_arrival: Option<i32>, timer_base: i32, min_interval: i32) -> i32 {
+		match self.arrivals.get(self.idx) {
+			Some(&arrival) => {
+				self.idx += 1;
+				// 到着時刻は絶対時刻とし、処理つぶれで起床が遅れても後ろにずらさない
+				// 最小到着間隔より詰まった到着は後ろにずらす
+				let arrival = match last_arrival {
+					Some(last) => arrival.max(last.saturating_add(min_interval)),
+					None => arrival,
+				};
+				arrival - timer_base
+			},
+			// リストを使い切ったらそれ以降は到着しない
+			None => i32::MAX,
+		}
+	}
+}
@@ -11,10 +11,30 @@ type LogCallback = fn(
 ) -> ();
  */
 
-pub trait ProcessCallback: FnMut(&String, i32, ProcessState, i32, i32, bool) -> ()
+/**
+ログ種類
+状態ログの他、状態遷移を伴わない区間の通知を区別する
+*/
+#[derive(Clone, Copy)]
+pub enum ProcessLog {
+	/// 状態ログ(区間中のプロセス状態)
+	STATE(ProcessState),
+	/// 優先度逆転区間(ブロック元プロセスID)
+	INVERSION(i32),
+}
+impl std::fmt::Display for ProcessLog {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			ProcessLog::STATE(state) => write!(f,"{}",state),
+			ProcessLog::INVERSION(_) => write!(f,"INVERSION"),
+		}
+	}
+}
+
+pub trait ProcessCallback: FnMut(&String, i32, ProcessLog, i32, i32, bool)
 {}
 
 impl<T> ProcessCallback for T
-	where T: FnMut(&String, i32, ProcessState, i32, i32, bool) -> ()
+	where T: FnMut(&String, i32, ProcessLog, i32, i32, bool)
 {}
 
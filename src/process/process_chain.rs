@@ -0,0 +1,68 @@
+/// ジョブの起動元履歴
+/// 起動トリガを辿った各プロセスの(プロセスID, 起動時刻)を起動順に保持する
+pub type JobChain = Vec<(i32,i32)>;
+
+/**
+プロセスチェーン
+先頭プロセスの起動から末尾プロセスの完了までをEnd-to-Endレイテンシとして集計する
+各プロセスは直前のプロセスの起動トリガ(trigger=NAME)で起動される必要がある
+*/
+pub struct ProcessChain {
+	pub name: String,			// チェーン名
+	pub procs: Vec<String>,		// 構成プロセス名(起動順)
+	head_id: i32,				// 先頭プロセスID
+	tail_id: i32,				// 末尾プロセスID
+	// 統計
+	cnt_complete: i32,			// 完了回数
+	max_latency: i32,			// End-to-Endレイテンシ最大値
+	min_latency: Option<i32>,	// End-to-Endレイテンシ最小値
+	total_latency: i64,			// End-to-Endレイテンシ合計
+}
+
+impl ProcessChain {
+	pub fn new(name: String, procs: Vec<String>) -> ProcessChain {
+		ProcessChain {
+			name,
+			procs,
+			head_id: -1,
+			tail_id: -1,
+			cnt_complete: 0,
+			max_latency: 0,
+			min_latency: None,
+			total_latency: 0,
+		}
+	}
+
+	/**
+	先頭/末尾プロセスID設定
+	*/
+	pub fn set_ids(&mut self, head_id: i32, tail_id: i32) {
+		self.head_id = head_id;
+		self.tail_id = tail_id;
+	}
+
+	/**
+	ジョブ完了チェック
+	末尾プロセスのジョブが先頭プロセスの起動を起点としていればレイテンシを記録する
+	*/
+	pub fn check(&mut self, id: i32, job_chain: &JobChain, cpu_time: i32) {
+		if id != self.tail_id {
+			return;
+		}
+		if let Some((_, release)) = job_chain.iter().find(|(_id, _)| *_id == self.head_id) {
+			let latency = cpu_time - release;
+			self.cnt_complete += 1;
+			self.max_latency = self.max_latency.max(latency);
+			self.min_latency = Some(self.min_latency.map_or(latency, |min| min.min(latency)));
+			self.total_latency += latency as i64;
+		}
+	}
+
+	pub fn get_result(&self) -> String {
+		let (min, ave) = match self.min_latency {
+			Some(min) => (min.to_string(), format!("{:.2}", self.total_latency as f64 / self.cnt_complete as f64)),
+			None => ("-".to_string(), "-".to_string()),
+		};
+		format!("[{:40}] | {:7} | {:>11} | {:>11} | {:>11}", &self.name, &self.cnt_complete, min, ave, self.max_latency)
+	}
+}
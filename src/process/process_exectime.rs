@@ -0,0 +1,141 @@
+use crate::process::process_rand::ProcessRand;
+
+/// 処理時間の決定方法
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExecTimeMode {
+	/// [BCET,WCET]の一様乱数
+	UNIFORM,
+	/// 正規分布乱数を[BCET,WCET]で打ち切り
+	NORMAL,
+	/// ヒストグラム(区間上限:度数)の度数で区間を選び、区間内は一様乱数
+	HISTOGRAM,
+	/// 指定値(値:重み)から重みに従って選択
+	CHOICE,
+	/// 測定値を順番に再生(最後まで使ったら先頭に戻る)
+	REPLAY,
+	/// 測定値から一様に選択
+	SAMPLE,
+}
+impl std::fmt::Display for ExecTimeMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			ExecTimeMode::UNIFORM => write!(f,"uniform"),
+			ExecTimeMode::NORMAL => write!(f,"normal"),
+			ExecTimeMode::HISTOGRAM => write!(f,"hist"),
+			ExecTimeMode::CHOICE => write!(f,"choice"),
+			ExecTimeMode::REPLAY => write!(f,"replay"),
+			ExecTimeMode::SAMPLE => write!(f,"sample"),
+		}
+	}
+}
+
+/**
+処理時間分布
+ジョブ毎の処理時間を乱数で生成する(未指定時は処理時間欄を順番に繰り返す)
+WCETは処理時間欄の先頭(Max)を使用する
+*/
+#[derive(Clone)]
+pub struct ExecTimeDist {
+	pub mode: ExecTimeMode,
+	bcet: i32,					// 最短処理時間
+	mean: Option<f64>,			// 平均(NORMAL), 未指定時は(BCET+WCET)/2
+	sigma: Option<f64>,			// 標準偏差(NORMAL), 未指定時は(WCET-BCET)/6
+	values: Vec<(i32, i32)>,	// (区間上限 or 値, 度数 or 重み), 測定値は重み1
+	idx: usize,					// 次に再生する測定値idx(REPLAY)
+}
+
+impl ExecTimeDist {
+	pub fn new(mode: ExecTimeMode, bcet: i32, mean: Option<f64>, sigma: Option<f64>, values: Vec<(i32, i32)>) -> ExecTimeDist {
+		let mut values = values;
+		// ヒストグラムは区間上限順に並べる
+		if let ExecTimeMode::HISTOGRAM = mode {
+			values.sort_by_key(|(value, _)| *value);
+		}
+		ExecTimeDist {
+			mode,
+			bcet,
+			mean,
+			sigma,
+			values,
+			idx: 0,
+		}
+	}
+
+	/**
+	次のジョブの処理時間を生成
+	wcet: 処理時間欄の先頭(Max)
+	*/
+	pub fn sample(&mut self, wcet: i32, rand: &mut ProcessRand) -> i32 {
+		let bcet = self.bcet.min(wcet);
+		match self.mode {
+			ExecTimeMode::UNIFORM => rand.range(bcet, wcet),
+			ExecTimeMode::NORMAL => {
+				let mean = self.mean.unwrap_or((bcet + wcet) as f64 / 2.0);
+				let sigma = self.sigma.unwrap_or((wcet - bcet) as f64 / 6.0);
+				// 範囲外は再抽選し、収束しなければ範囲内に丸める
+				let mut value = mean;
+				for _ in 0..100 {
+					value = rand.normal(mean, sigma);
+					if bcet as f64 <= value && value <= wcet as f64 {
+						break;
+					}
+				}
+				(value.round() as i32).clamp(bcet, wcet)
+			},
+			ExecTimeMode::HISTOGRAM => {
+				let idx = self.choose(rand);
+				let lower = match idx {
+					0 => bcet.min(self.values[0].0),
+					_ => self.values[idx - 1].0 + 1,
+				};
+				rand.range(lower, self.values[idx].0)
+			},
+			ExecTimeMode::CHOICE => self.values[self.choose(rand)].0,
+			ExecTimeMode::REPLAY => {
+				let value = self.values[self.idx].0;
+				self.idx += 1;
+				if self.idx >= self.values.len() {
+					self.idx = 0;
+				}
+				value
+			},
+			ExecTimeMode::SAMPLE => self.values[rand.range(0, self.values.len() as i32 - 1) as usize].0,
+		}
+	}
+
+	/**
+	測定値(REPLAY/SAMPLE)から処理時間を生成するか判定
+	*/
+	pub fn is_measured(&self) -> bool {
+		matches!(self.mode, ExecTimeMode::REPLAY | ExecTimeMode::SAMPLE)
+	}
+
+	/**
+	最短処理時間
+	*/
+	pub fn get_bcet(&self) -> i32 {
+		self.bcet
+	}
+
+	/**
+	値の最大値(測定値であれば最悪処理時間)
+	*/
+	pub fn get_max_value(&self) -> i32 {
+		self.values.iter().map(|(value, _)| *value).max().unwrap_or(0)
+	}
+
+	/**
+	度数/重みに従って値を選択
+	*/
+	fn choose(&self, rand: &mut ProcessRand) -> usize {
+		let total: i64 = self.values.iter().map(|(_, weight)| *weight as i64).sum();
+		let mut point = (rand.next_f64() * total as f64) as i64;
+		for (idx, (_, weight)) in self.values.iter().enumerate() {
+			if point < *weight as i64 {
+				return idx;
+			}
+			point -= *weight as i64;
+		}
+		self.values.len() - 1
+	}
+}
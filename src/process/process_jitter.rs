@@ -0,0 +1,64 @@
+use crate::process::process_rand::ProcessRand;
+
+/// リリースジッタの発生方法
+#[derive(Clone, Copy, PartialEq)]
+pub enum JitterMode {
+	/// 毎回上限値だけ遅れる
+	MAX,
+	/// 上限値と0を交互に繰り返す(起動間隔が最も詰まる)
+	ALTERNATE,
+	/// [0,上限値]の一様乱数
+	RANDOM,
+	/// 指定した値を順番に繰り返す
+	SEQUENCE,
+}
+
+/**
+リリースジッタ
+周期起動のタイミングから実際にREADYになるまでの遅れを生成する
+*/
+#[derive(Clone)]
+pub struct ReleaseJitter {
+	mode: JitterMode,
+	values: Vec<i32>,		// ジッタ値[上限値] or [値1,値2,...]
+	idx: usize,				// 次に使用する値のidx
+}
+
+impl ReleaseJitter {
+	pub fn new(mode: JitterMode, values: Vec<i32>) -> ReleaseJitter {
+		ReleaseJitter {
+			mode,
+			values,
+			idx: 0,
+		}
+	}
+
+	/**
+	次の起動に対するジッタを生成
+	*/
+	pub fn next(&mut self, rand: &mut ProcessRand) -> i32 {
+		if self.values.is_empty() {
+			return 0;
+		}
+		let bound = self.values[0];
+		let jitter = match self.mode {
+			JitterMode::MAX => bound,
+			JitterMode::ALTERNATE => {
+				if self.idx == 0 { bound } else { 0 }
+			},
+			JitterMode::RANDOM => rand.range(0, bound),
+			JitterMode::SEQUENCE => self.values[self.idx],
+		};
+		// idx更新
+		let len = match self.mode {
+			JitterMode::ALTERNATE => 2,
+			JitterMode::SEQUENCE => self.values.len(),
+			_ => 1,
+		};
+		self.idx += 1;
+		if self.idx >= len {
+			self.idx = 0;
+		}
+		jitter
+	}
+}
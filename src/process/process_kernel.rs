@@ -0,0 +1,96 @@
+use crate::process::process_callback::ProcessCallback;
+use crate::process::process_callback::ProcessLog;
+use crate::process::process_state::ProcessState;
+
+/// カーネル擬似プロセス名(予約名のためプロセス名には使用不可)
+pub const KERNEL_NAME: &str = "kernel";
+
+/**
+カーネル擬似プロセス
+タスク切り替え、ISR入口/出口、スケジューラ起動のオーバーヘッドを集計する
+いずれかのコアでカーネル処理を実行中であればRUNNING、それ以外はWAITINGとして記録する
+*/
+pub struct ProcessKernel<T>
+	where T: ProcessCallback
+{
+	pub id: i32,					// プロセスID
+	pub name: String,				// プロセス名
+	state: ProcessState,			// 状態
+	// ログ情報
+	log_cpu_time: i32,				// 状態開始時CPU時間
+	log_callback: T,				// ログ生成時のコールバック関数
+	// 統計
+	time_busy: i32,					// カーネル処理時間の合計(全コア)
+	cnt_task_switch: i32,			// タスク切り替え回数
+	cnt_isr_entry: i32,				// ISR入口回数
+}
+
+impl<T> ProcessKernel<T>
+	where T: ProcessCallback
+{
+	pub fn new(id: i32, cb: T) -> ProcessKernel<T> {
+		ProcessKernel {
+			id,
+			name: KERNEL_NAME.to_string(),
+			state: ProcessState::WAITING,
+			log_cpu_time: 0,
+			log_callback: cb,
+			time_busy: 0,
+			cnt_task_switch: 0,
+			cnt_isr_entry: 0,
+		}
+	}
+
+	/**
+	時間経過
+	running_cores: カーネル処理を実行中のコア数
+	*/
+	pub fn go(&mut self, cpu_time:i32, elapse:i32, running_cores:i32) {
+		// 状態変化があればログ登録
+		let state = if running_cores > 0 { ProcessState::RUNNING } else { ProcessState::WAITING };
+		if !self.is_same_state(state) {
+			self.push_log(cpu_time);
+			self.state = state;
+		}
+		self.time_busy += elapse * running_cores;
+	}
+
+	pub fn count_task_switch(&mut self) {
+		self.cnt_task_switch += 1;
+	}
+
+	pub fn count_isr_entry(&mut self) {
+		self.cnt_isr_entry += 1;
+	}
+
+	/**
+	集計結果
+	runtime: トレース時間(全コア合計)
+	*/
+	pub fn get_result(&self, runtime:i32) -> String {
+		let userate = self.time_busy as f32 / runtime as f32 * 100.0;
+		format!("[{:40}] | {:10.2}% {:15} | {:7} | {:7} | {:10}", &self.name, userate, "", "", "", "")
+	}
+
+	pub fn get_count_result(&self) -> String {
+		format!("[{:40}] | {:11} | {:9} | {:10}", &self.name, &self.cnt_task_switch, &self.cnt_isr_entry, &self.time_busy)
+	}
+
+	fn is_same_state(&self, state: ProcessState) -> bool {
+		matches!((self.state, state), (ProcessState::RUNNING, ProcessState::RUNNING) | (ProcessState::WAITING, ProcessState::WAITING))
+	}
+
+	fn push_log(&mut self, cpu_time:i32) {
+		// ログを通知
+		(self.log_callback)(
+			&self.name,
+			self.id,
+			ProcessLog::STATE(self.state),
+			self.log_cpu_time,
+			cpu_time,
+			false,
+		);
+		// ログクリア
+		self.log_cpu_time = cpu_time;
+	}
+}
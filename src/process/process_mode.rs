@@ -0,0 +1,74 @@
+/**
+モード別パラメータ
+[ProcessInfo]の個別設定 mode.NAME.cycle=N, mode.NAME.time=T1,T2,..., mode.NAME.active=true/false で指定する
+未指定の項目は[ProcessInfo]の基本設定を使用する
+*/
+#[derive(Clone, Default)]
+pub struct ModeParam {
+	pub cycle: Option<i32>,				// 起動周期
+	pub time_proc: Option<Vec<i32>>,	// 処理時間[Max,Ave1,Ave2,...]
+	pub active: Option<bool>,			// 起動有無, falseで周期起動しない
+}
+
+/**
+モード別集計
+同じモードに複数回遷移した場合は合算する
+*/
+pub struct ModeStat {
+	pub name: String,				// モード名
+	cnt_enter: i32,					// 遷移回数
+	duration: i32,					// 滞在時間
+	busy: i32,						// CPU使用時間(全コア合計)
+	proc_max_rate: Vec<f32>,		// プロセス毎の最大占有率
+	proc_overrun: Vec<i32>,			// プロセス毎の処理つぶれ回数
+}
+
+impl ModeStat {
+	pub fn new(name: String, proc_num: usize) -> ModeStat {
+		ModeStat {
+			name,
+			cnt_enter: 0,
+			duration: 0,
+			busy: 0,
+			proc_max_rate: vec![0.0; proc_num],
+			proc_overrun: vec![0; proc_num],
+		}
+	}
+
+	pub fn enter(&mut self) {
+		self.cnt_enter += 1;
+	}
+
+	/**
+	時間経過
+	busy: 経過時間中のCPU使用時間(全コア合計)
+	*/
+	pub fn go(&mut self, elapse: i32, busy: i32) {
+		self.duration += elapse;
+		self.busy += busy;
+	}
+
+	/**
+	モード滞在中のプロセス集計を追加
+	*/
+	pub fn add_proc(&mut self, idx: usize, max_rate: f32, overrun: i32) {
+		self.proc_max_rate[idx] = self.proc_max_rate[idx].max(max_rate);
+		self.proc_overrun[idx] += overrun;
+	}
+
+	/**
+	集計結果
+	core_num: コア数
+	*/
+	pub fn get_result(&self, core_num: usize) -> String {
+		let userate = match self.duration {
+			0 => 0.0,
+			_ => self.busy as f32 / (self.duration as f32 * core_num as f32) * 100.0,
+		};
+		format!("[{:40}] | {:7} | {:10} | {:10.2}%", &self.name, &self.cnt_enter, &self.duration, userate)
+	}
+
+	pub fn get_proc_result(&self, idx: usize, proc_name: &str) -> String {
+		format!("[{:40}] | {:10.2}% | {:7}", format!("{}/{}", self.name, proc_name), self.proc_max_rate[idx], self.proc_overrun[idx])
+	}
+}
@@ -0,0 +1,135 @@
+use crate::process::process_callback::ProcessCallback;
+use crate::process::process_rand::splitmix64;
+use crate::process::process_tracer::ProcessTracer;
+
+/**
+モンテカルロ試行結果
+試行毎のプロセス最大占有率、処理つぶれ/デッドラインミス回数、最大遅れ時間、全体占有率を集計する
+*/
+pub struct MonteCarloStats {
+	names: Vec<String>,				// プロセス名
+	peak_rates: Vec<Vec<f64>>,		// プロセス毎・試行毎の最大占有率
+	overruns: Vec<Vec<f64>>,		// プロセス毎・試行毎の処理つぶれ回数
+	deadline_misses: Vec<Vec<f64>>,	// プロセス毎・試行毎のデッドラインミス回数
+	lateness: Vec<Vec<f64>>,		// プロセス毎・試行毎の最大遅れ時間(ジョブ完了した試行のみ)
+	total_rates: Vec<f64>,			// 試行毎の全体占有率
+}
+
+impl MonteCarloStats {
+	pub fn new(names: Vec<String>) -> MonteCarloStats {
+		let num = names.len();
+		MonteCarloStats {
+			names,
+			peak_rates: vec![vec![]; num],
+			overruns: vec![vec![]; num],
+			deadline_misses: vec![vec![]; num],
+			lateness: vec![vec![]; num],
+			total_rates: vec![],
+		}
+	}
+
+	/**
+	試行毎のシード
+	試行間で乱数系列が相関しないようsplitmix64で導出する
+	*/
+	pub fn run_seed(seed: u64, run: i32) -> u64 {
+		splitmix64(seed ^ run as u64)
+	}
+
+	/**
+	1試行分の結果を追加
+	*/
+	pub fn add<T>(&mut self, tracer: &ProcessTracer<T>)
+		where T: ProcessCallback
+	{
+		for (idx, proc) in tracer.procs.iter().enumerate() {
+			self.peak_rates[idx].push(proc.get_max_cpu_use_rate() as f64);
+			let (overrun, deadline_miss, lateness) = proc.get_delay_count();
+			self.overruns[idx].push(overrun as f64);
+			self.deadline_misses[idx].push(deadline_miss as f64);
+			if let Some(lateness) = lateness {
+				self.lateness[idx].push(lateness as f64);
+			}
+		}
+		self.total_rates.push(tracer.cpu_use_rate as f64);
+	}
+
+	pub fn output(&self) {
+		println!("[Monte Carlo: {} runs]", self.total_rates.len());
+		// 占有率
+		println!();
+		println!("[{:40}] | mean-load  | p50-load   | p90-load   | p99-load   | p99.9-load | max-load", "Process Name");
+		println!("-{0:-<40}--+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-", "");
+		for (idx, name) in self.names.iter().enumerate() {
+			println!("{}", MonteCarloStats::get_load_result(name, &self.peak_rates[idx]));
+		}
+		println!("-{0:-<40}--+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-", "");
+		println!("{}", MonteCarloStats::get_load_result("all", &self.total_rates));
+		// 処理つぶれ回数
+		println!();
+		println!("[{:40}] | overrun-runs | mean-ovr   | p50-ovr    | p90-ovr    | p99-ovr    | p99.9-ovr  | max-ovr", "Process Name");
+		println!("-{0:-<40}--+-{0:-<12}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-", "");
+		for (idx, name) in self.names.iter().enumerate() {
+			println!("{}", MonteCarloStats::get_count_result(name, &self.overruns[idx]));
+		}
+		// デッドラインミス回数/遅れ時間
+		println!();
+		println!("[{:40}] | DL-miss-runs | mean-miss  | p50-miss   | p90-miss   | p99-miss   | p99.9-miss | max-miss   | p99.9-late | max-late", "Process Name");
+		println!("-{0:-<40}--+-{0:-<12}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-+-{0:-<10}-", "");
+		for (idx, name) in self.names.iter().enumerate() {
+			let (late_p999, late_max) = match self.lateness[idx].is_empty() {
+				true => ("-".to_string(), "-".to_string()),
+				false => {
+					let sorted = MonteCarloStats::sort(&self.lateness[idx]);
+					(MonteCarloStats::percentile(&sorted, 99.9).to_string(), sorted[sorted.len() - 1].to_string())
+				},
+			};
+			println!("{} | {:>10} | {:>10}", MonteCarloStats::get_count_result(name, &self.deadline_misses[idx]), late_p999, late_max);
+		}
+	}
+
+	pub(crate) fn get_load_result(name: &str, values: &[f64]) -> String {
+		let sorted = MonteCarloStats::sort(values);
+		let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+		format!("[{:40}] | {:9.2}% | {:9.2}% | {:9.2}% | {:9.2}% | {:9.2}% | {:9.2}%", name, mean,
+			MonteCarloStats::percentile(&sorted, 50.0), MonteCarloStats::percentile(&sorted, 90.0),
+			MonteCarloStats::percentile(&sorted, 99.0), MonteCarloStats::percentile(&sorted, 99.9),
+			MonteCarloStats::max(values))
+	}
+
+	/**
+	回数の集計結果
+	発生した試行数と、試行毎の回数の平均/パーセンタイル/最大
+	*/
+	pub(crate) fn get_count_result(name: &str, values: &[f64]) -> String {
+		let sorted = MonteCarloStats::sort(values);
+		let runs = values.iter().filter(|cnt| **cnt > 0.0).count();
+		let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+		format!("[{:40}] | {:12} | {:10.2} | {:10} | {:10} | {:10} | {:10} | {:10}", name, runs, mean,
+			MonteCarloStats::percentile(&sorted, 50.0), MonteCarloStats::percentile(&sorted, 90.0),
+			MonteCarloStats::percentile(&sorted, 99.0), MonteCarloStats::percentile(&sorted, 99.9),
+			MonteCarloStats::max(values))
+	}
+
+	fn sort(values: &[f64]) -> Vec<f64> {
+		let mut sorted = values.to_vec();
+		sorted.sort_by(|a, b| a.total_cmp(b));
+		sorted
+	}
+
+	/**
+	パーセンタイル(nearest-rank法)
+	sorted: 昇順ソート済みの値
+	*/
+	fn percentile(sorted: &[f64], p: f64) -> f64 {
+		if sorted.is_empty() {
+			return 0.0;
+		}
+		let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+		sorted[rank.clamp(1, sorted.len()) - 1]
+	}
+
+	fn max(values: &[f64]) -> f64 {
+		values.iter().cloned().fold(0.0, f64::max)
+	}
+}
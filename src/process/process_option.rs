@@ -0,0 +1,64 @@
+use crate::process::process_jitter::ReleaseJitter;
+use crate::process::process_arrival::SporadicArrival;
+use crate::process::process_resource::CriticalSection;
+use crate::process::process_resource::MutexSection;
+use crate::process::process_resource::IntLockSection;
+use crate::process::process_sync::SyncPoint;
+use crate::process::process_exectime::ExecTimeDist;
+use crate::process::process_mode::ModeParam;
+use std::collections::HashMap;
+
+/**
+プロセス個別設定
+[ProcessInfo]の各行末尾に key=value 形式で指定する
+未指定の項目はNoneとなり、トレース設定または既定値を使用する
+*/
+#[derive(Clone, Default)]
+pub struct ProcessOption {
+	/// ラウンドロビンのタイムクォンタム(quantum=N)
+	pub time_quantum: Option<i32>,
+	/// 相対デッドライン(deadline=N), 未指定時は起動周期
+	pub time_deadline: Option<i32>,
+	/// 初回起動時刻(offset=N), 未指定時は起動周期経過時点
+	pub time_offset: Option<i32>,
+	/// リリースジッタ(jitter=N or jitter=N1,N2,..., jitter_mode=max/alt/random)
+	pub release_jitter: Option<ReleaseJitter>,
+	/// スポラディック起動(sporadic=dense/random/list, max_interval=N, arrivals=T1,T2,...)
+	/// 指定時は起動周期欄を最小到着間隔として扱う
+	pub sporadic: Option<SporadicArrival>,
+	/// 多重起動上限(act_limit=N), 実行中ジョブを含めた起動要求数の上限
+	/// 指定時は上限までの起動要求をキューイングし、超過分は破棄として記録する
+	pub act_limit: Option<i32>,
+	/// クリティカルセクション(resource=NAME:OFFSET:LENGTH,...)
+	pub critical_sections: Vec<CriticalSection>,
+	/// ミューテックス占有区間(mutex=NAME:OFFSET:LENGTH,...), 優先度継承で排他
+	pub mutex_sections: Vec<MutexSection>,
+	/// 割り当てコア(core=K), 未指定時はコア0
+	pub core: Option<usize>,
+	/// 割り込みレベル(level=N), INTRのみ指定可能, 指定時は全INTRで指定し高レベルのみ多重割り込み可能
+	pub level: Option<i32>,
+	/// 割り込み禁止区間(intlock=OFFSET:LENGTH[:LEVEL],...)
+	pub intlock_sections: Vec<IntLockSection>,
+	/// プリエンプション閾値(threshold=N), TASKのみ有効
+	/// ディスパッチ後はTASK間で閾値の優先度で動作し、閾値より高い優先度のTASKにのみpreemptされる
+	pub threshold: Option<i32>,
+	/// スケジュールポイント(sched=T1,T2,...), ジョブ内処理時間
+	/// non-preemptiveでもスケジュールポイントでは優先度の高いTASKに切り替わる
+	pub sched_points: Vec<i32>,
+	/// 起動トリガ(trigger=NAME[:OFFSET]), 指定プロセスのジョブ完了時(OFFSET指定時はジョブ内処理時間到達時)に起動する
+	/// 指定時は起動周期による起動は行わない
+	pub trigger: Option<(String, Option<i32>)>,
+	/// 同期ポイント(send/recv=QUEUE:OFFSET,..., setflag/waitflag=FLAG:OFFSET,...), ジョブ内処理時間順
+	pub sync_points: Vec<SyncPoint>,
+	/// 処理時間分布(exec_mode=uniform/normal/hist/choice, bcet=N, mean=N, sigma=N, exec_values=V1:W1,...)
+	/// 指定時はジョブ毎の処理時間を乱数で生成し、処理時間欄の先頭をWCETとして使用する
+	pub exec_time: Option<ExecTimeDist>,
+	/// モード別パラメータ(mode.NAME.cycle=N, mode.NAME.time=T1,T2,..., mode.NAME.active=true/false)
+	/// [TraceInfo]のModeChangeでモードが切り替わった時点で適用する
+	pub modes: HashMap<String, ModeParam>,
+	/// 処理時間の基準周波数(ref_freq=N, MHz), 未指定時は[TraceInfo]のRefFreq
+	/// CPU周波数(CpuFreq)との比で処理時間をスケーリングする
+	pub ref_freq: Option<i32>,
+	/// 処理サイクル数(cycles=N1,N2,...), 指定時は処理時間欄の代わりに使用する(基準周波数1MHz相当)
+	pub cycles: Vec<i32>,
+}
@@ -0,0 +1,33 @@
+/// スケジューリングポリシー
+#[derive(Clone, Copy, PartialEq)]
+pub enum SchedPolicy {
+	/// 固定優先度: 優先度の高いプロセスを選択
+	FP,
+	/// Earliest-Deadline-First: 絶対デッドラインが最も近いプロセスを選択
+	EDF,
+}
+impl std::fmt::Display for SchedPolicy {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			SchedPolicy::FP => write!(f,"FP"),
+			SchedPolicy::EDF => write!(f,"EDF"),
+		}
+	}
+}
+
+/// マルチコア時のコア割り当て方式
+#[derive(Clone, Copy, PartialEq)]
+pub enum CoreAssign {
+	/// パーティション: プロセスは割り当てコアでのみ実行する
+	PARTITIONED,
+	/// グローバル: READYプロセスの上位からコア数分をいずれかのコアで実行する
+	GLOBAL,
+}
+impl std::fmt::Display for CoreAssign {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			CoreAssign::PARTITIONED => write!(f,"PARTITIONED"),
+			CoreAssign::GLOBAL => write!(f,"GLOBAL"),
+		}
+	}
+}
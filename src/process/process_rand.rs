@@ -0,0 +1,69 @@
+/**
+疑似乱数生成器(xorshift64*)
+トレース結果を再現できるよう、シードを指定して生成する
+*/
+#[derive(Clone)]
+pub struct ProcessRand {
+	state: u64,
+}
+
+/**
+splitmix64
+近いシードから相関の無いシードを導出する
+*/
+pub fn splitmix64(seed: u64) -> u64 {
+	let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	z ^ (z >> 31)
+}
+
+impl ProcessRand {
+	pub fn new(seed: u64) -> ProcessRand {
+		// シードをsplitmix64で攪拌して初期状態とする(0は不可)
+		let mut z = splitmix64(seed);
+		if z == 0 {
+			z = 0x9E37_79B9_7F4A_7C15;
+		}
+		ProcessRand {
+			state: z,
+		}
+	}
+
+	pub fn next_u64(&mut self) -> u64 {
+		let mut x = self.state;
+		x ^= x >> 12;
+		x ^= x << 25;
+		x ^= x >> 27;
+		self.state = x;
+		x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+	}
+
+	/**
+	[0,1)の一様乱数
+	*/
+	pub fn next_f64(&mut self) -> f64 {
+		(self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+	}
+
+	/**
+	正規分布乱数(Box-Muller法)
+	*/
+	pub fn normal(&mut self, mean: f64, sigma: f64) -> f64 {
+		// ln(0)を避けるため(0,1]とする
+		let u1 = 1.0 - self.next_f64();
+		let u2 = self.next_f64();
+		mean + sigma * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+	}
+
+	/**
+	[min,max]の一様整数乱数
+	*/
+	pub fn range(&mut self, min: i32, max: i32) -> i32 {
+		if max <= min {
+			return min;
+		}
+		let width = (max as i64 - min as i64 + 1) as u64;
+		(min as i64 + (self.next_u64() % width) as i64) as i32
+	}
+}
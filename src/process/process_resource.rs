@@ -0,0 +1,149 @@
+/**
+クリティカルセクション
+ジョブ開始からの処理時間offsetから処理時間length分、共有リソースを占有する
+占有中はImmediate Priority Ceiling Protocolによりリソースの優先度上限で動作する
+*/
+#[derive(Clone)]
+pub struct CriticalSection {
+	pub resource: String,		// リソース名
+	pub offset: i32,			// ジョブ内の占有開始時間
+	pub length: i32,			// 占有時間
+	pub ceiling: i32,			// 優先度上限
+}
+
+impl CriticalSection {
+	pub fn new(resource: String, offset: i32, length: i32) -> CriticalSection {
+		CriticalSection {
+			resource,
+			offset,
+			length,
+			ceiling: 0,
+		}
+	}
+
+	/**
+	ジョブ内の処理時間runでリソースを占有しているか判定
+	*/
+	pub fn is_locked(&self, run: i32) -> bool {
+		self.offset <= run && run < self.offset + self.length
+	}
+
+	/**
+	ジョブ内の処理時間runから次にロック/アンロックするまでの時間
+	*/
+	pub fn get_next_point(&self, run: i32) -> Option<i32> {
+		if run < self.offset {
+			Some(self.offset - run)
+		} else if run < self.offset + self.length {
+			Some(self.offset + self.length - run)
+		} else {
+			None
+		}
+	}
+}
+
+/**
+割り込み禁止区間
+ジョブ開始からの処理時間offsetから処理時間length分、割り込みレベルlevel以下の割り込みをマスクする
+*/
+#[derive(Clone)]
+pub struct IntLockSection {
+	pub offset: i32,			// ジョブ内の禁止開始時間
+	pub length: i32,			// 禁止時間
+	pub level: i32,				// マスクする割り込みレベル上限(全割り込み禁止はi32::MAX)
+}
+
+impl IntLockSection {
+	pub fn new(offset: i32, length: i32, level: i32) -> IntLockSection {
+		IntLockSection {
+			offset,
+			length,
+			level,
+		}
+	}
+
+	/**
+	ジョブ内の処理時間runで割り込み禁止中か判定
+	*/
+	pub fn is_locked(&self, run: i32) -> bool {
+		self.offset <= run && run < self.offset + self.length
+	}
+
+	/**
+	ジョブ内の処理時間runから次に禁止/許可するまでの時間
+	*/
+	pub fn get_next_point(&self, run: i32) -> Option<i32> {
+		if run < self.offset {
+			Some(self.offset - run)
+		} else if run < self.offset + self.length {
+			Some(self.offset + self.length - run)
+		} else {
+			None
+		}
+	}
+}
+
+/**
+ミューテックス占有区間
+ジョブ開始からの処理時間offsetでロックを要求し、処理時間length分占有する
+他プロセスが占有中であればBLOCKEDで待機し、占有プロセスは優先度継承により待機プロセスの優先度で動作する
+*/
+#[derive(Clone)]
+pub struct MutexSection {
+	pub mutex: String,			// ミューテックス名
+	pub offset: i32,			// ジョブ内のロック要求時間
+	pub length: i32,			// 占有時間
+	pub locked: bool,			// ロック中
+	pub done: bool,				// 今回ジョブで占有完了済み
+}
+
+impl MutexSection {
+	pub fn new(mutex: String, offset: i32, length: i32) -> MutexSection {
+		MutexSection {
+			mutex,
+			offset,
+			length,
+			locked: false,
+			done: false,
+		}
+	}
+
+	/**
+	ジョブ内の処理時間runから次にロック/アンロックするまでの時間
+	*/
+	pub fn get_next_point(&self, run: i32) -> Option<i32> {
+		if self.done {
+			None
+		} else if !self.locked {
+			Some((self.offset - run).max(0))
+		} else {
+			Some((self.offset + self.length - run).max(0))
+		}
+	}
+}
+
+/// ミューテックス操作要求
+pub enum MutexRequest {
+	/// ロック要求(占有区間idx)
+	LOCK(usize),
+	/// アンロック要求(占有区間idx)
+	UNLOCK(usize),
+}
+
+/**
+ミューテックス状態
+*/
+#[derive(Default)]
+pub struct MutexState {
+	pub owner: Option<usize>,		// 占有プロセスidx
+	pub waiters: Vec<usize>,		// 待機プロセスidx(待機順)
+}
+
+impl MutexState {
+	pub fn new() -> MutexState {
+		MutexState {
+			owner: None,
+			waiters: vec![],
+		}
+	}
+}
@@ -4,6 +4,10 @@ pub enum ProcessState {
 	WAITING,
 	READY,
 	RUNNING,
+	/// ミューテックス待ち
+	BLOCKED,
+	/// メッセージキュー/イベントフラグ待ち
+	EVWAIT,
 }
 impl std::fmt::Display for ProcessState {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -12,6 +16,8 @@ impl std::fmt::Display for ProcessState {
 			ProcessState::WAITING => write!(f,"WAITING"),
 			ProcessState::READY => write!(f,"READY"),
 			ProcessState::RUNNING => write!(f,"RUNNING"),
+			ProcessState::BLOCKED => write!(f,"BLOCKED"),
+			ProcessState::EVWAIT => write!(f,"EVWAIT"),
 		}
 	}
 }
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+/// 同期オブジェクト種類
+#[derive(Clone, Copy, PartialEq)]
+pub enum SyncKind {
+	/// メッセージキュー
+	QUEUE,
+	/// イベントフラグ
+	FLAG,
+	/// 拡張タスクのイベント(タスク名.イベント名)
+	EVENT,
+}
+impl std::fmt::Display for SyncKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			SyncKind::QUEUE => write!(f,"QUEUE"),
+			SyncKind::FLAG => write!(f,"FLAG"),
+			SyncKind::EVENT => write!(f,"EVENT"),
+		}
+	}
+}
+
+/// 同期操作種類
+#[derive(Clone, Copy, PartialEq)]
+pub enum SyncOp {
+	/// 送信(キュー)/セット(フラグ, イベント)
+	POST,
+	/// 受信(キュー)/待ち(フラグ, イベント)
+	TAKE,
+}
+
+/**
+同期ポイント
+ジョブ開始からの処理時間offsetで同期オブジェクトを操作する
+受信/待ちでデータ/フラグ/イベントが無ければEVWAITで待機する
+*/
+#[derive(Clone)]
+pub struct SyncPoint {
+	pub object: String,			// 同期オブジェクト名
+	pub kind: SyncKind,			// 同期オブジェクト種類
+	pub op: SyncOp,				// 操作種類
+	pub offset: i32,			// ジョブ内の操作時間
+	pub done: bool,				// 今回ジョブで操作済み
+}
+
+impl SyncPoint {
+	pub fn new(object: String, kind: SyncKind, op: SyncOp, offset: i32) -> SyncPoint {
+		SyncPoint {
+			object,
+			kind,
+			op,
+			offset,
+			done: false,
+		}
+	}
+}
+
+/**
+同期オブジェクト状態
+キューはデータ数、フラグ/イベントはセット有無(0/1)をcountで保持する
+イベントは所有タスクのみ待機でき、所有タスクが休止中のセットは無視する
+*/
+pub struct SyncObject {
+	pub kind: SyncKind,				// 同期オブジェクト種類
+	pub owner: Option<usize>,		// イベント所有タスクidx
+	pub count: i32,					// データ数/フラグ
+	pub waiters: VecDeque<usize>,	// 待機プロセスidx(待機順)
+	// 統計
+	pub cnt_post: i32,				// 送信/セット回数
+	pub cnt_take: i32,				// 受信/待ち解除回数
+	pub max_count: i32,				// 最大データ数
+}
+
+impl SyncObject {
+	pub fn new(kind: SyncKind) -> SyncObject {
+		SyncObject {
+			kind,
+			owner: None,
+			count: 0,
+			waiters: VecDeque::new(),
+			cnt_post: 0,
+			cnt_take: 0,
+			max_count: 0,
+		}
+	}
+
+	pub fn get_result(&self, name: &str) -> String {
+		format!("[{:40}] | {:5} | {:9} | {:9} | {:9}", name, self.kind.to_string(), &self.cnt_post, &self.cnt_take, &self.max_count)
+	}
+}
@@ -1,113 +1,604 @@
+use std::collections::HashMap;
+
 use crate::process::process::ProcessKind;
 use crate::process::process::Process;
 use crate::process::process_callback::ProcessCallback;
+use crate::process::process_kernel::ProcessKernel;
+use crate::process::process_chain::ProcessChain;
+use crate::process::process_alarm::ActivationTable;
+use crate::process::process_alarm::AlarmAction;
+use crate::process::process_mode::ModeStat;
+use crate::process::process_sync::SyncObject;
+use crate::process::process_sync::SyncKind;
+use crate::process::process_sync::SyncOp;
+use crate::process::process_rand::splitmix64;
+use crate::process::process_policy::SchedPolicy;
+use crate::process::process_policy::CoreAssign;
+use crate::process::process_resource::MutexRequest;
+use crate::process::process_resource::MutexState;
+
+/// トレース設定
+pub struct TraceConfig {
+	/// タスク間でpreemptを実施するかどうか
+	pub task_use_preempt: bool,
+	/// INTRプロセスのスケジューリングポリシー
+	pub intr_policy: SchedPolicy,
+	/// TASKプロセスのスケジューリングポリシー
+	pub task_policy: SchedPolicy,
+	/// 同優先度TASK間のラウンドロビンのタイムクォンタム(0で無効)
+	pub time_quantum: i32,
+	/// 乱数シード
+	pub seed: u64,
+	/// コア数
+	pub core_num: usize,
+	/// コア割り当て方式
+	pub core_assign: CoreAssign,
+	/// マイグレーションコスト
+	pub migration_cost: i32,
+	/// タスク切り替えオーバーヘッド(コンテキスト復帰)
+	pub overhead_task_switch: i32,
+	/// ISR入口オーバーヘッド
+	pub overhead_isr_entry: i32,
+	/// ISR出口オーバーヘッド
+	pub overhead_isr_exit: i32,
+	/// スケジューラ起動オーバーヘッド
+	pub overhead_scheduler: i32,
+	/// プロセスチェーン定義(チェーン名, 構成プロセス名)
+	pub chains: Vec<(String,Vec<String>)>,
+	/// アラーム/スケジュールテーブル定義
+	pub alarms: Vec<ActivationTable>,
+	/// モード切り替えスケジュール(モード名, 切り替え時刻), 時刻順
+	pub mode_changes: Vec<(String,i32)>,
+	/// CPU周波数(MHz), 0で周波数スケーリング無効
+	pub cpu_freq: i32,
+	/// 処理時間の基準周波数(MHz), 0でCPU周波数と同じ
+	pub ref_freq: i32,
+	/// 周波数切り替えスケジュール(周波数, 切り替え時刻), 時刻順
+	pub freq_changes: Vec<(i32,i32)>,
+}
+
+impl TraceConfig {
+	/// カーネルオーバーヘッドを模擬するか判定
+	pub fn use_kernel(&self) -> bool {
+		self.overhead_task_switch > 0 || self.overhead_isr_entry > 0 || self.overhead_isr_exit > 0 || self.overhead_scheduler > 0
+	}
+}
+
+impl Default for TraceConfig {
+	fn default() -> TraceConfig {
+		TraceConfig {
+			task_use_preempt: true,
+			intr_policy: SchedPolicy::FP,
+			task_policy: SchedPolicy::FP,
+			time_quantum: 0,
+			seed: 0,
+			core_num: 1,
+			core_assign: CoreAssign::PARTITIONED,
+			migration_cost: 0,
+			overhead_task_switch: 0,
+			overhead_isr_entry: 0,
+			overhead_isr_exit: 0,
+			overhead_scheduler: 0,
+			chains: vec![],
+			alarms: vec![],
+			mode_changes: vec![],
+			cpu_freq: 0,
+			ref_freq: 0,
+			freq_changes: vec![],
+		}
+	}
+}
 
 pub struct ProcessTracer<T>
 	where T: ProcessCallback
 {
 	// プロセスリスト
 	pub procs: Vec<Process<T>>,
-	// プロセストレース情報
-	active_proc_idx: Option<usize>,
+	// プロセストレース情報(コア毎のアクティブプロセス)
+	active_proc_idx: Vec<Option<usize>>,
 	// トレース設定
-	task_use_preempt: bool,
-	// CPU占有率
+	config: TraceConfig,
+	use_resource: bool,
+	// 割り込みレベル/割り込み禁止区間の使用有無
+	use_intlock: bool,
+	// 割り込みレベルの使用有無
+	use_level: bool,
+	// プリエンプション閾値の使用有無
+	use_threshold: bool,
+	// ミューテックス状態
+	mutexes: HashMap<String,MutexState>,
+	// メッセージキュー/イベントフラグ状態
+	syncs: HashMap<String,SyncObject>,
+	// カーネル擬似プロセス
+	pub kernel: Option<ProcessKernel<T>>,
+	// プロセスチェーン
+	chains: Vec<ProcessChain>,
+	// アラーム/スケジュールテーブル
+	alarms: Vec<ActivationTable>,
+	// モード別集計
+	modes: Vec<ModeStat>,
+	// 現在モードidx
+	mode_cur: usize,
+	// 次のモード切り替えidx
+	mode_change_idx: usize,
+	// モード開始時のプロセス毎処理つぶれ回数
+	mode_overrun_base: Vec<i32>,
+	// 周波数別集計(周波数, 滞在時間, CPU使用時間)
+	freq_stats: Vec<(i32,i32,i32)>,
+	// 現在の周波数別集計idx
+	freq_cur: usize,
+	// 次の周波数切り替えidx
+	freq_change_idx: usize,
+	// トレース時間
+	trace_time: i32,
+	// CPU占有率(全コア平均)
 	pub cpu_use_rate: f32,
-	cpu_use_busy: i32,
-	cpu_use_idle: i32,
+	// コア毎のCPU使用カウント
+	cpu_use_busy: Vec<i32>,
+	cpu_use_idle: Vec<i32>,
 }
 
 impl<T> ProcessTracer<T>
 	where T: ProcessCallback
 {
 	// コンストラクタ
-	pub fn new<'a>(procs: Vec<Process<T>>, task_use_preempt: bool) -> ProcessTracer<T> {
+	pub fn new(procs: Vec<Process<T>>, config: TraceConfig, kernel_cb: T) -> ProcessTracer<T> {
+		let core_num = config.core_num;
+		// カーネルオーバーヘッド指定があればカーネル擬似プロセスを作成
+		let kernel = if config.use_kernel() {
+			Some(ProcessKernel::new(procs.len() as i32, kernel_cb))
+		} else {
+			None
+		};
 		let mut data = ProcessTracer {
 			procs,
-			active_proc_idx: None,
-			task_use_preempt,
+			active_proc_idx: vec![None; core_num],
+			config,
+			use_resource: false,
+			use_intlock: false,
+			use_level: false,
+			use_threshold: false,
+			mutexes: HashMap::new(),
+			syncs: HashMap::new(),
+			kernel,
+			chains: vec![],
+			alarms: vec![],
+			modes: vec![],
+			mode_cur: 0,
+			mode_change_idx: 0,
+			mode_overrun_base: vec![],
+			freq_stats: vec![],
+			freq_cur: 0,
+			freq_change_idx: 0,
+			trace_time: 0,
 			cpu_use_rate: 0.0,
-			cpu_use_busy: 0,
-			cpu_use_idle: 0,
+			cpu_use_busy: vec![0; core_num],
+			cpu_use_idle: vec![0; core_num],
 		};
 
 		// プロセスIDを設定
 		for (idx, _proc) in data.procs.iter_mut().enumerate() {
 			_proc.id = idx as i32;
+			// タイムクォンタム未指定のプロセスはトレース設定を適用
+			_proc.set_default_time_quantum(data.config.time_quantum);
+			// 乱数はプロセス毎に異なる系列とする
+			// 隣接するシードの系列が重ならないようsplitmix64で導出する
+			_proc.init_random(splitmix64(data.config.seed ^ idx as u64));
+			// ISR出口オーバーヘッド
+			if let ProcessKind::INTR = _proc.kind {
+				_proc.set_kernel_exit(data.config.overhead_isr_exit);
+			}
+			// 割り当てコアチェック
+			if _proc.core >= core_num {
+				panic!("invalid core: {} (CoreNum={}) in {}", _proc.core, core_num, _proc.name);
+			}
+		}
+		// リソース毎の優先度上限を算出
+		// リソースを使用するプロセスの最高優先度を優先度上限とする
+		let mut ceilings: HashMap<String,i32> = HashMap::new();
+		for _proc in data.procs.iter() {
+			for section in _proc.get_critical_sections().iter() {
+				let ceiling = ceilings.entry(section.resource.clone()).or_insert(_proc.priority);
+				if _proc.priority > *ceiling {
+					*ceiling = _proc.priority;
+				}
+			}
+		}
+		for _proc in data.procs.iter_mut() {
+			_proc.set_resource_ceiling(&ceilings);
+		}
+		data.use_resource = !ceilings.is_empty();
+		data.use_level = data.procs.iter().any(|proc| proc.has_hw_level());
+		// 割り込みレベルは優先度と別の尺度のため、使用時は全INTRで指定が必要
+		for _proc in data.procs.iter() {
+			match _proc.kind {
+				ProcessKind::INTR => {
+					if data.use_level && !_proc.has_hw_level() {
+						panic!("invalid level: {} has no level while other INTR has", _proc.name);
+					}
+				},
+				ProcessKind::TASK => {
+					if _proc.has_hw_level() {
+						panic!("invalid level: {} is not INTR", _proc.name);
+					}
+				},
+			}
+			// マスクレベル指定の割り込み禁止区間は割り込みレベルと比較するため、割り込みレベル使用時のみ指定可能
+			if !data.use_level && _proc.has_intlock_level() {
+				panic!("invalid intlock: LEVEL in {} requires level of INTR", _proc.name);
+			}
+		}
+		data.use_intlock = data.use_level || data.procs.iter().any(|proc| proc.has_intlock());
+		// プリエンプション閾値を使用する場合はTASK間のpreemptionを閾値で判定する
+		// 閾値未指定のTASKはタスクpreemption設定に従う
+		data.use_threshold = data.procs.iter().any(|proc| proc.has_threshold());
+		if data.use_threshold {
+			for _proc in data.procs.iter_mut() {
+				_proc.set_default_threshold(data.config.task_use_preempt);
+			}
+		}
+		// 起動トリガ登録
+		for idx in 0..data.procs.len() {
+			if let Some((name, offset)) = data.procs[idx].trigger.clone() {
+				match data.get_proc_idx(&name) {
+					Some(src) => data.procs[src].add_trigger(idx, offset),
+					None => panic!("invalid trigger: {} in {}", name, data.procs[idx].name),
+				}
+			}
+		}
+		// プロセスチェーン作成
+		for (name, procs) in std::mem::take(&mut data.config.chains) {
+			let mut ids = vec![];
+			for proc_name in procs.iter() {
+				match data.get_proc_idx(proc_name) {
+					Some(idx) => ids.push(idx),
+					None => panic!("invalid ChainInfo: {} in {}", proc_name, name),
+				}
+			}
+			// 各プロセスが直前のプロセスの起動トリガで起動されるかチェック
+			for pair in ids.windows(2) {
+				let linked = match &data.procs[pair[1]].trigger {
+					Some((src, _)) => *src == data.procs[pair[0]].name,
+					None => false,
+				};
+				if !linked {
+					panic!("invalid ChainInfo: {} is not triggered by {} in {}", data.procs[pair[1]].name, data.procs[pair[0]].name, name);
+				}
+			}
+			let mut chain = ProcessChain::new(name, procs);
+			chain.set_ids(ids[0] as i32, ids[ids.len() - 1] as i32);
+			data.chains.push(chain);
+		}
+		// アラーム/スケジュールテーブル作成
+		for mut alarm in std::mem::take(&mut data.config.alarms) {
+			let mut targets = vec![];
+			for names in alarm.get_target_names() {
+				let mut actions = vec![];
+				for proc_name in names.iter() {
+					// TASK.EVENTはイベントセット
+					if proc_name.contains('.') {
+						data.syncs.entry(proc_name.clone()).or_insert_with(|| SyncObject::new(SyncKind::EVENT));
+						actions.push(AlarmAction::SETEVENT(proc_name.clone()));
+						continue;
+					}
+					match data.get_proc_idx(proc_name) {
+						Some(idx) => actions.push(AlarmAction::ACTIVATE(idx)),
+						None => panic!("invalid AlarmInfo: {} in {}", proc_name, alarm.name),
+					}
+				}
+				targets.push(actions);
+			}
+			// 起動先プロセスは起動周期による起動を行わない
+			for action in targets.iter().flatten() {
+				if let AlarmAction::ACTIVATE(idx) = action {
+					data.procs[*idx].set_external();
+				}
+			}
+			alarm.set_targets(targets);
+			data.alarms.push(alarm);
+		}
+		// 周波数スケーリング設定
+		if data.config.cpu_freq > 0 {
+			let ref_freq = match data.config.ref_freq {
+				0 => data.config.cpu_freq,
+				freq => freq,
+			};
+			for _proc in data.procs.iter_mut() {
+				_proc.init_freq(ref_freq, data.config.cpu_freq);
+			}
+			data.freq_stats.push((data.config.cpu_freq, 0, 0));
+		} else if !data.config.freq_changes.is_empty() || data.procs.iter().any(|proc| proc.has_ref_freq()) {
+			panic!("invalid TraceInfo: CpuFreq is required for FreqChange, ref_freq and cycles");
+		}
+		// モード別集計作成
+		if !data.config.mode_changes.is_empty() {
+			let proc_num = data.procs.len();
+			// 時刻0で切り替えなければ切り替えまでは基本設定(default)
+			let use_default = data.config.mode_changes[0].1 > 0;
+			if use_default {
+				data.modes.push(ModeStat::new("default".to_string(), proc_num));
+			}
+			for (name, _) in data.config.mode_changes.iter() {
+				if !data.modes.iter().any(|mode| mode.name == *name) {
+					data.modes.push(ModeStat::new(name.clone(), proc_num));
+				}
+			}
+			for _proc in data.procs.iter() {
+				for name in _proc.get_mode_names() {
+					if !data.modes.iter().any(|mode| mode.name == *name) {
+						panic!("invalid mode: {} in {} is not in ModeChange", name, _proc.name);
+					}
+				}
+			}
+			if use_default {
+				data.modes[0].enter();
+			}
+			data.mode_overrun_base = vec![0; proc_num];
+		}
+		// メッセージキュー/イベントフラグ/イベント作成
+		for _proc in data.procs.iter() {
+			for point in _proc.get_sync_points().iter() {
+				let object = data.syncs.entry(point.object.clone()).or_insert_with(|| SyncObject::new(point.kind));
+				if object.kind != point.kind {
+					panic!("invalid sync object: {} is used as both {} and {}", point.object, object.kind, point.kind);
+				}
+				// イベント待ちは拡張タスクのみ
+				if let (SyncKind::EVENT, SyncOp::TAKE, ProcessKind::INTR) = (point.kind, point.op, &_proc.kind) {
+					panic!("invalid waitevent: {} is not TASK", _proc.name);
+				}
+			}
+		}
+		// イベント所有タスク設定
+		let mut owners = vec![];
+		for (name, object) in data.syncs.iter() {
+			if let SyncKind::EVENT = object.kind {
+				let task = name.split('.').next().unwrap();
+				match data.get_proc_idx(task) {
+					Some(idx) => owners.push((name.clone(), idx)),
+					None => panic!("invalid event: {}", name),
+				}
+			}
+		}
+		for (name, idx) in owners {
+			data.syncs.get_mut(&name).unwrap().owner = Some(idx);
+		}
+		// ミューテックス作成
+		for _proc in data.procs.iter() {
+			for name in _proc.get_mutex_names() {
+				data.mutexes.entry(name).or_insert_with(MutexState::new);
+			}
 		}
 
 		data
 	}
 
 	pub fn run(&mut self, trace_time: i32) {
+		self.trace(trace_time, false);
+	}
+
+	/**
+	トレース実行
+	tick_step: trueで1tick毎に時間を進める(イベント駆動との比較用)
+	*/
+	fn trace(&mut self, trace_time: i32, tick_step: bool) {
 		// 計測時間作成
 		let timemax = trace_time;
-		let mut disp_cycle: i32 = 0;
+		self.trace_time = trace_time;
+		let mut disp_next: i32 = 1000000;
 		let mut disp_count: i32 = 0;
 		// プロセス初期設定
 		self.start_proc();
+		// 時刻0で満了するアラーム/スケジュールテーブル
+		self.check_alarm(0);
+		// 時刻0のモード切り替え
+		self.check_mode(0);
+		// 時刻0の周波数切り替え
+		self.check_freq(0);
 		// 計測時間分のトレース開始
-		for cpu_time in 1..timemax {
+		// 1tick毎に処理すると長時間トレースで処理が終わらないため、
+		// 次に状態変化が発生する時間までまとめて時間を進める
+		let mut cpu_time: i32 = 1;
+		while cpu_time < timemax {
 			// アクティブプロセスの終了チェック
 			self.check_running_proc();
 			// ディスパッチチェック
 			self.check_dispatch(cpu_time);
+			// ディスパッチしたプロセスがミューテックス待ち/同期待ちになれば再度ディスパッチ
+			while self.check_active_mutex(cpu_time) || self.check_active_sync(cpu_time) {
+				self.check_running_proc();
+				self.check_dispatch(cpu_time);
+			}
+			// 次イベント発生までの時間を算出
+			// 次イベントが発生するtickまではディスパッチ判定に変化は無い
+			let elapse = if tick_step {
+				1
+			} else {
+				self.get_next_event(cpu_time).min(timemax - cpu_time)
+			};
+			let event_time = cpu_time + elapse - 1;
+			// ブロッキング時間計測
+			self.check_blocking(elapse);
+			// カーネル処理時間計測
+			self.check_kernel(cpu_time, elapse);
+			// 割り込みマスク待ち時間計測
+			self.check_masking(elapse);
 			// 時間を進める
-			self.go_time(cpu_time, 1);
+			self.go_time(event_time, elapse);
 			// CPU使用カウント
-			self.check_cpu_use();
+			self.check_cpu_use(elapse);
+			// モード切り替え
+			if !self.modes.is_empty() {
+				self.check_mode(event_time);
+			}
+			// 周波数切り替え
+			if !self.freq_stats.is_empty() {
+				self.check_freq(event_time);
+			}
 
 			// 進捗出力: 1sec経過を通知
-			disp_cycle += 1;
-			if disp_cycle >= 1000000 {
+			while disp_next <= event_time {
 				disp_count += 1;
-				disp_cycle = 0;
+				disp_next = disp_next.saturating_add(1000000);
 				println!("{} sec elapsed.", disp_count);
 			}
+			cpu_time += elapse;
+		}
+		// 最後のモードを集計
+		if !self.modes.is_empty() {
+			self.leave_mode();
 		}
 		// CPU占有率計算
-		let runtime = timemax as f32;
-		self.cpu_use_rate = self.cpu_use_busy as f32 / runtime * 100 as f32;
+		let runtime = timemax as f32 * self.config.core_num as f32;
+		self.cpu_use_rate = self.cpu_use_busy.iter().sum::<i32>() as f32 / runtime * 100.0;
 	}
 
 	fn start_proc(&mut self) {
-		for proc in self.procs.iter_mut() {
-			//proc.init(0);
+		for _proc in self.procs.iter_mut() {
+			//_proc.init(0);
+			_proc.start();
 		}
 	}
 
 	fn check_running_proc(&mut self) {
-		match &mut self.active_proc_idx {
-			Some(_idx) => {
-				let proc = &mut self.procs[*_idx];
-				if proc.is_waiting() {
-					self.active_proc_idx = None;
+		for core in 0..self.config.core_num {
+			if let Some(_idx) = self.active_proc_idx[core] {
+				let proc = &mut self.procs[_idx];
+				// 処理完了でRUNNINGから抜けていればCPU解放
+				if !proc.is_running() {
+					self.active_proc_idx[core] = None;
 				}
-			},
-			None => ()
+			}
 		}
 	}
 
 	fn check_dispatch(&mut self, cpu_time:i32) {
-		// READYプロセスから起動するプロセスを選択
-		let next_proc = self.get_prior_proc();
-		match next_proc {
-			Some(_next_proc_idx) => {
-				// 現アクティブプロセスがいればREADYに
-				match &mut self.active_proc_idx {
-					Some(_active_proc_idx) => {
-						let active_proc = &mut self.procs[*_active_proc_idx];
-						active_proc.preempt(cpu_time);
+		match self.config.core_assign {
+			CoreAssign::PARTITIONED => {
+				// コア毎にディスパッチ
+				for core in 0..self.config.core_num {
+					self.check_dispatch_core(core, cpu_time);
+				}
+			},
+			CoreAssign::GLOBAL => {
+				self.check_dispatch_global(cpu_time);
+			}
+		}
+	}
+
+	/**
+	グローバルスケジューリングのディスパッチ
+	優先度の高いREADYプロセスから順に、空きコアまたは最も優先度の低い実行中プロセスのコアに割り当てる
+	 */
+	fn check_dispatch_global(&mut self, cpu_time:i32) {
+		while let Some(_ready_proc_idx) = self.get_global_ready_proc() {
+			match self.get_global_core(_ready_proc_idx) {
+				Some(core) => self.switch_proc(core, _ready_proc_idx, cpu_time),
+				None => break,
+			}
+		}
+		// タイムスライス満了であれば同優先度のTASKへ交代
+		for core in 0..self.config.core_num {
+			if let Some(_active_proc_idx) = self.active_proc_idx[core] {
+				if self.procs[_active_proc_idx].is_slice_expired() {
+					match self.get_rotate_ready_proc(_active_proc_idx) {
+						Some(_next_proc_idx) => self.switch_proc(core, _next_proc_idx, cpu_time),
+						None => self.procs[_active_proc_idx].restart_slice(),
+					}
+				}
+			}
+		}
+	}
+
+	/**
+	グローバルスケジューリングの割り当てコア選択
+	空きコアがあれば前回実行コアを優先し、無ければpreempt可能な実行中プロセスのうち最も優先度の低いもののコアを選択
+	 */
+	fn get_global_core(&self, ready_proc_idx: usize) -> Option<usize> {
+		let ready_proc = &self.procs[ready_proc_idx];
+		// 割り込みマスクで実行できないコアは対象外
+		let enabled: Vec<bool> = (0..self.config.core_num).map(|core| !self.is_masked(ready_proc_idx, self.get_mask(core))).collect();
+		// 空きコア
+		if enabled[ready_proc.core] && self.active_proc_idx[ready_proc.core].is_none() {
+			return Some(ready_proc.core);
+		}
+		if let Some(core) = (0..self.config.core_num).find(|core| enabled[*core] && self.active_proc_idx[*core].is_none()) {
+			return Some(core);
+		}
+		// preempt対象のコア
+		let mut result: Option<usize> = None;
+		for (core, active_proc_idx) in self.active_proc_idx.iter().enumerate() {
+			if !enabled[core] {
+				continue;
+			}
+			let active_proc = &self.procs[active_proc_idx.unwrap()];
+			if self.is_nestable(active_proc) && self.check_prior_process(active_proc, ready_proc) {
+				match result {
+					Some(_result_core) => {
+						let selected = &self.procs[self.active_proc_idx[_result_core].unwrap()];
+						if self.check_prior_ready(selected, active_proc) {
+							result = Some(core);
+						}
 					},
 					None => {
-						// 何もしない
+						result = Some(core);
+					}
+				}
+			}
+		}
+		result
+	}
+
+	/**
+	コアのアクティブプロセス交代
+	 */
+	fn switch_proc(&mut self, core: usize, next_proc_idx: usize, cpu_time:i32) {
+		// 現アクティブプロセスがいればREADYに
+		if let Some(_active_proc_idx) = self.active_proc_idx[core] {
+			self.procs[_active_proc_idx].preempt(cpu_time);
+		}
+		// アクティブプロセス更新
+		self.active_proc_idx[core] = Some(next_proc_idx);
+		// 新アクティブプロセスをディスパッチ
+		let next_proc = &mut self.procs[next_proc_idx];
+		if let CoreAssign::GLOBAL = self.config.core_assign {
+			next_proc.migrate(core, self.config.migration_cost);
+		}
+		// カーネルオーバーヘッド
+		if let Some(kernel) = self.kernel.as_mut() {
+			match next_proc.kind {
+				ProcessKind::TASK => {
+					// TASKはスケジューラで選択されてコンテキスト復帰する
+					next_proc.add_kernel(self.config.overhead_scheduler + self.config.overhead_task_switch);
+					kernel.count_task_switch();
+				},
+				ProcessKind::INTR => {
+					// INTRはジョブ開始時のみISR入口処理
+					if !next_proc.is_job_started() {
+						next_proc.add_kernel(self.config.overhead_isr_entry);
+						kernel.count_isr_entry();
 					}
 				}
-				// アクティブプロセス更新
-				self.active_proc_idx = next_proc;
-				// 新アクティブプロセスをディスパッチ
-				let next_proc = &mut self.procs[_next_proc_idx];
-				next_proc.dispatch(cpu_time);
+			}
+		}
+		next_proc.dispatch(cpu_time);
+	}
+
+	/**
+	プロセスが指定コアで実行可能か判定
+	 */
+	fn is_on_core(&self, proc: &Process<T>, core: usize) -> bool {
+		match self.config.core_assign {
+			CoreAssign::PARTITIONED => proc.core == core,
+			CoreAssign::GLOBAL => true,
+		}
+	}
+
+	fn check_dispatch_core(&mut self, core: usize, cpu_time:i32) {
+		// READYプロセスから起動するプロセスを選択
+		let next_proc = self.get_prior_proc(core);
+		match next_proc {
+			Some(_next_proc_idx) => {
+				self.switch_proc(core, _next_proc_idx, cpu_time);
 			},
 			None => {
 				// 何もしない
@@ -115,14 +606,15 @@ impl<T> ProcessTracer<T>
 		}
 	}
 
-	fn get_prior_proc<'a>(&mut self) -> Option<usize> {
+	fn get_prior_proc(&mut self, core: usize) -> Option<usize> {
 		let mut result: Option<usize> = None;
-		let ready_proc_idx = self.get_prior_ready_proc();
-		match self.active_proc_idx {
+		let ready_proc_idx = self.get_prior_ready_proc(core);
+		match self.active_proc_idx[core] {
 			Some(_active_proc_idx) => {
 				let active_proc = &self.procs[_active_proc_idx];
-				if active_proc.multi_intr {
+				if self.is_nestable(active_proc) {
 					// 多重割込み許可ならRAEDYプロセスとディスパッチ要否判定
+					// スケジュールポイントでは多重割込み禁止でも判定する
 					//let ready_proc_idx = self.get_prior_ready_proc();
 					// READYプロセスの存在チェック
 					match ready_proc_idx {
@@ -130,7 +622,7 @@ impl<T> ProcessTracer<T>
 							// RUNNINGプロセスとREADYプロセスを比較してディスパッチ要否を判定
 							let ready_proc= &self.procs[_ready_proc_idx];
 							// ディスパッチ要であればREADYプロセスを選択
-							if self.check_prior_process(&active_proc, &ready_proc) {
+							if self.check_prior_process(active_proc, ready_proc) {
 								result = Some(_ready_proc_idx);
 							}
 						},
@@ -149,42 +641,84 @@ impl<T> ProcessTracer<T>
 				result = ready_proc_idx;
 			}
 		}
+		// タイムスライス満了であれば同優先度のTASKへ交代
+		if result.is_none() {
+			if let Some(_active_proc_idx) = self.active_proc_idx[core] {
+				if self.procs[_active_proc_idx].is_slice_expired() {
+					result = self.get_rotate_ready_proc(_active_proc_idx);
+					if result.is_none() {
+						// 交代先が無ければそのまま次のスライスを開始
+						self.procs[_active_proc_idx].restart_slice();
+					}
+				}
+			}
+		}
 		//
 		result
 	}
 
 	/**
-	READY状態のプロセスから優先度の高いものを選択
+	ラウンドロビン交代先選択
+	アクティブプロセスと同優先度のREADY状態TASKから選択
 	 */
-	fn get_prior_ready_proc(&mut self) -> Option<usize> {
+	fn get_rotate_ready_proc(&self, active_proc_idx: usize) -> Option<usize> {
+		let active_proc = &self.procs[active_proc_idx];
 		let mut result: Option<usize> = None;
-		let mut max_pri = 0;
-		let mut max_ready = 0;
-		for (i, proc) in self.procs.iter_mut().enumerate() {
-			if proc.is_ready() {
-				match result {
-					Some(_proc) => {
-						// READYプロセスが複数あれば優先度で判定
-						if proc.priority > max_pri {
-							result = Some(i);
-							max_pri = proc.priority;
-							max_ready = proc.timer_ready;
-						} else if proc.priority == max_pri {
-							// 優先度が同じ場合はFCFS方式でREADY時間が長い方を選択
-							if proc.timer_ready > max_ready {
+		for (i, proc) in self.procs.iter().enumerate() {
+			if proc.is_ready() && self.is_on_core(proc, active_proc.core) && proc.get_task_priority() == active_proc.get_task_priority() {
+				if let ProcessKind::TASK = proc.kind {
+					match result {
+						Some(_result_idx) => {
+							if self.check_prior_ready(proc, &self.procs[_result_idx]) {
 								result = Some(i);
-								max_pri = proc.priority;
-								max_ready = proc.timer_ready;
 							}
-						} else {
-							// 優先度が低い場合は何もしない
+						},
+						None => {
+							result = Some(i);
+						}
+					}
+				}
+			}
+		}
+		result
+	}
+
+	/**
+	指定コアのREADY状態のプロセスから優先度の高いものを選択
+	 */
+	fn get_prior_ready_proc(&mut self, core: usize) -> Option<usize> {
+		let mask = self.get_mask(core);
+		self.select_ready_proc(|i, proc| self.is_on_core(proc, core) && !self.is_masked(i, mask))
+	}
+
+	/**
+	グローバルスケジューリングでREADY状態のプロセスから優先度の高いものを選択
+	割り込みマスクはコア毎に判定し、いずれかのコアで実行可能なプロセスを対象とする
+	 */
+	fn get_global_ready_proc(&self) -> Option<usize> {
+		let masks: Vec<Option<(i32, usize)>> = (0..self.config.core_num).map(|core| self.get_mask(core)).collect();
+		self.select_ready_proc(|i, _| masks.iter().any(|mask| !self.is_masked(i, *mask)))
+	}
+
+	/**
+	READY状態かつ条件を満たすプロセスから優先度の高いものを選択
+	 */
+	fn select_ready_proc<F>(&self, filter: F) -> Option<usize>
+		where F: Fn(usize, &Process<T>) -> bool
+	{
+		let mut result: Option<usize> = None;
+		for (i, proc) in self.procs.iter().enumerate() {
+			if proc.is_ready() && filter(i, proc) {
+				match result {
+					Some(_result_idx) => {
+						// READYプロセスが複数あればポリシーに従って判定
+						if self.check_prior_ready(proc, &self.procs[_result_idx]) {
+							result = Some(i);
 						}
 					},
 					None => {
 						// 初回出現は無条件セット
 						result = Some(i);
-						max_pri = proc.priority;
-						max_ready = proc.timer_ready;
 					}
 				}
 			}
@@ -193,8 +727,143 @@ impl<T> ProcessTracer<T>
 		result
 	}
 
+	/**
+	割り込みマスク状態
+	指定コアで割り込み禁止区間を処理中のプロセスがあれば、マスクレベルとそのプロセスを返す
+	禁止区間中にpreemptされたプロセスのマスクも有効とする
+	グローバルスケジューリングではプロセスが最後に実行したコアのみをマスクする
+	 */
+	fn get_mask(&self, core: usize) -> Option<(i32, usize)> {
+		if !self.use_intlock {
+			return None;
+		}
+		let mut result: Option<(i32, usize)> = None;
+		for (i, proc) in self.procs.iter().enumerate() {
+			if proc.core == core {
+				if let Some(level) = proc.get_mask_level() {
+					match result {
+						Some((_level, _)) if _level >= level => (),
+						_ => result = Some((level, i)),
+					}
+				}
+			}
+		}
+		result
+	}
+
+	/**
+	プロセスが割り込みマスクにより実行できないか判定
+	マスク中はマスクしているプロセスとマスクレベルより高いINTRのみ実行可能
+	 */
+	fn is_masked(&self, idx: usize, mask: Option<(i32, usize)>) -> bool {
+		match mask {
+			Some((level, holder)) => {
+				if idx == holder {
+					return false;
+				}
+				let proc = &self.procs[idx];
+				match proc.kind {
+					ProcessKind::INTR => proc.get_hw_level().is_none_or(|hw_level| hw_level <= level),
+					ProcessKind::TASK => true,
+				}
+			},
+			None => false,
+		}
+	}
+
+	/**
+	割り込みマスク待ち時間計測
+	READYのINTRが割り込みマスクにより待たされている時間を計測する
+	実行可能な全てのコアでマスクされている場合をマスク待ちとする
+	 */
+	fn check_masking(&mut self, elapse:i32) {
+		if !self.use_intlock {
+			return;
+		}
+		let masks: Vec<Option<(i32, usize)>> = (0..self.config.core_num).map(|core| self.get_mask(core)).collect();
+		let mut masked = vec![];
+		for (i, proc) in self.procs.iter().enumerate() {
+			if let ProcessKind::INTR = proc.kind {
+				if proc.is_ready() && (0..self.config.core_num).filter(|core| self.is_on_core(proc, *core)).all(|core| self.is_masked(i, masks[core])) {
+					masked.push(i);
+				}
+			}
+		}
+		for i in masked {
+			self.procs[i].add_masked_time(elapse);
+		}
+	}
+
+	/**
+	READYプロセス同士の比較
+	procがselectedより優先して選択されるときtrueを返す
+	 */
+	fn check_prior_ready(&self, proc: &Process<T>, selected: &Process<T>) -> bool {
+		// 割り込みレベル使用時のINTR同士は割り込みレベルが高い方を選択
+		if let (true, ProcessKind::INTR, ProcessKind::INTR) = (self.use_level, &proc.kind, &selected.kind) {
+			if proc.get_hw_level() != selected.get_hw_level() {
+				return proc.get_hw_level() > selected.get_hw_level();
+			}
+		}
+		// 双方EDFであれば絶対デッドラインが近い方を選択
+		if self.is_edf(proc) && self.is_edf(selected) {
+			let deadline = proc.get_abs_deadline();
+			let selected_deadline = selected.get_abs_deadline();
+			if deadline != selected_deadline {
+				return deadline < selected_deadline;
+			}
+			// デッドラインが同じ場合は優先度で判定
+		}
+		let (priority, selected_priority) = match (&proc.kind, &selected.kind) {
+			(ProcessKind::TASK, ProcessKind::TASK) => (proc.get_task_priority(), selected.get_task_priority()),
+			_ => (proc.get_cur_priority(), selected.get_cur_priority()),
+		};
+		if priority > selected_priority {
+			true
+		} else if priority == selected_priority {
+			if proc.get_time_quantum() > 0 && selected.get_time_quantum() > 0 {
+				// ラウンドロビン対象同士であれば先にREADYに遷移した方を選択
+				proc.ready_since < selected.ready_since
+			} else {
+				// 優先度が同じ場合はFCFS方式でREADY時間が長い方を選択
+				proc.timer_ready > selected.timer_ready
+			}
+		} else {
+			// 優先度が低い場合は何もしない
+			false
+		}
+	}
+
+	/**
+	RUNNINGプロセスへの割り込み(preempt)判定を行うか判定
+	多重割込み許可またはスケジュールポイントで判定する
+	割り込みレベル使用時のINTRは多重割込み指定によらず割り込みレベルで判定する
+	 */
+	fn is_nestable(&self, running: &Process<T>) -> bool {
+		if self.use_level {
+			if let ProcessKind::INTR = running.kind {
+				return true;
+			}
+		}
+		running.multi_intr || running.is_sched_point()
+	}
+
 	fn check_prior_process(&self, running: &Process<T>, ready: &Process<T>) -> bool
 		where T: ProcessCallback
+	{
+		// プリエンプション閾値はTASK間でのみ適用する
+		let running_priority = match (&running.kind, &ready.kind) {
+			(ProcessKind::TASK, ProcessKind::TASK) => running.get_task_priority(),
+			_ => running.get_cur_priority(),
+		};
+		self.check_prior_process_by(running, ready, running_priority)
+	}
+
+	/**
+	RUNNINGプロセスを優先度running_priorityとしてREADYプロセスがディスパッチ可能か判定
+	 */
+	fn check_prior_process_by(&self, running: &Process<T>, ready: &Process<T>, running_priority: i32) -> bool
+		where T: ProcessCallback
 	{
 		// アクティブプロセスが割り込みのとき、タスクの割り込み不可
 		if let ProcessKind::TASK = ready.kind {
@@ -203,13 +872,47 @@ impl<T> ProcessTracer<T>
 			}
 		}
 
-		// READYプロセスの優先度が同じか低いときはディスパッチ不可
-		if running.priority >= ready.priority {
-			return false;
+		// 割り込み禁止区間中はマスクレベルより高いINTRのみ割り込み可能
+		if let Some(level) = running.get_mask_level() {
+			match ready.kind {
+				ProcessKind::INTR => {
+					if ready.get_hw_level().is_none_or(|hw_level| hw_level <= level) {
+						return false;
+					}
+				},
+				ProcessKind::TASK => {
+					return false;
+				}
+			}
+		}
+
+		// 割り込みレベル使用時のINTR同士は割り込みレベルが高い場合のみ多重割り込み可能
+		if let (ProcessKind::INTR, ProcessKind::INTR) = (&running.kind, &ready.kind) {
+			if self.use_level {
+				return ready.get_hw_level() > running.get_hw_level();
+			}
+		}
+
+		if self.is_edf(running) && self.is_edf(ready) {
+			// 双方EDFであればREADYプロセスの絶対デッドラインが同じか遅いときはディスパッチ不可
+			if running.get_abs_deadline() <= ready.get_abs_deadline() {
+				return false;
+			}
+		} else {
+			// READYプロセスの優先度が同じか低いときはディスパッチ不可
+			let ready_priority = match (&running.kind, &ready.kind) {
+				(ProcessKind::TASK, ProcessKind::TASK) => ready.get_task_priority(),
+				_ => ready.get_cur_priority(),
+			};
+			if running_priority >= ready_priority {
+				return false;
+			}
 		}
 
 		// タスクpreemptionが無効の場合はタスク間でのディスパッチ不可
-		if !self.task_use_preempt {
+		// ただしスケジュールポイントではディスパッチ可能
+		// プリエンプション閾値を使用する場合は閾値で判定済み
+		if !self.config.task_use_preempt && !self.use_threshold && !running.is_sched_point() {
 			if let ProcessKind::TASK = ready.kind {
 				if let ProcessKind::TASK = running.kind {
 					return false;
@@ -220,39 +923,1487 @@ impl<T> ProcessTracer<T>
 		true
 	}
 
-	fn go_time(&mut self, cpu_time:i32, elapse:i32) {
-		for proc in self.procs.iter_mut() {
-			proc.go(cpu_time, elapse);
-		}
+	/**
+	プロセスがEDFでスケジューリングされるか判定
+	 */
+	fn is_edf(&self, proc: &Process<T>) -> bool {
+		let policy = match proc.kind {
+			ProcessKind::INTR => self.config.intr_policy,
+			ProcessKind::TASK => self.config.task_policy,
+		};
+		policy == SchedPolicy::EDF
 	}
 
-	fn check_cpu_use(&mut self) {
-		match &mut self.active_proc_idx {
-			Some(_idx) => {
-				self.cpu_use_busy += 1;
-			},
-			None => {
-				self.cpu_use_idle += 1;
+	/**
+	ブロッキング時間計測
+	本来の優先度であればディスパッチされるはずのREADYプロセスが、
+	アクティブプロセスのリソース占有(優先度上限)により待たされている時間を計測する
+	 */
+	fn check_blocking(&mut self, elapse:i32) {
+		if !self.use_resource && self.mutexes.is_empty() {
+			return;
+		}
+		for core in 0..self.config.core_num {
+			if let Some(_active_proc_idx) = self.active_proc_idx[core] {
+				let active_proc = &self.procs[_active_proc_idx];
+				if active_proc.get_task_priority() == active_proc.priority {
+					// リソース占有していなければブロッキングなし
+					continue;
+				}
+				let mut blocked = vec![];
+				for (i, proc) in self.procs.iter().enumerate() {
+					if proc.is_ready() && self.is_on_core(proc, core)
+						&& self.check_prior_process_by(active_proc, proc, active_proc.priority)
+						&& !self.check_prior_process(active_proc, proc) {
+						blocked.push(i);
+					}
+				}
+				for i in blocked {
+					self.procs[i].add_blocked_time(elapse);
+				}
 			}
 		}
 	}
 
-	pub fn output_proc_result(&mut self) {
-		// キャプション出力
-		println!("[{:40}] | CPU-userate (occur point  )", "Process Name");
-		println!("-{0:-<40}--+-{0:-<27}-", "");
-		// 各プロセス情報出力
+	/**
+	カーネル処理時間計測
+	カーネル処理中のアクティブプロセスがいるコアはカーネルが実行中とする
+	 */
+	fn check_kernel(&mut self, cpu_time:i32, elapse:i32) {
+		if self.kernel.is_none() {
+			return;
+		}
+		let running_cores = self.active_proc_idx.iter()
+			.flatten()
+			.filter(|idx| self.procs[**idx].is_kernel_running())
+			.count() as i32;
+		if let Some(kernel) = self.kernel.as_mut() {
+			kernel.go(cpu_time, elapse, running_cores);
+		}
+	}
+
+	/**
+	次イベントまでの時間を取得
+	全プロセス、アラーム/スケジュールテーブルの中で最も早く状態変化が発生するまでの経過時間を返す
+	 */
+	fn get_next_event(&self, cpu_time:i32) -> i32 {
+		let mut elapse = i32::MAX;
 		for proc in self.procs.iter() {
-			println!("{}", proc);
+			elapse = elapse.min(proc.get_next_event());
 		}
-		// 全体CPU使用率出力
-		let delay: &str;
-		if self.cpu_use_rate > 100.0 {
-			delay = "(delayed!)";
-		} else {
-			delay = "";
+		// 満了時刻のtickで起動する
+		for alarm in self.alarms.iter() {
+			if let Some(expiry) = alarm.get_next_expiry() {
+				elapse = elapse.min(expiry - cpu_time + 1);
+			}
+		}
+		// モード切り替え時刻のtickで切り替える
+		if let Some((_, time)) = self.config.mode_changes.get(self.mode_change_idx) {
+			elapse = elapse.min(time - cpu_time + 1);
+		}
+		// 周波数切り替え時刻のtickで切り替える
+		if let Some((_, time)) = self.config.freq_changes.get(self.freq_change_idx) {
+			elapse = elapse.min(time - cpu_time + 1);
+		}
+		elapse
+	}
+
+	fn go_time(&mut self, cpu_time:i32, elapse:i32) {
+		// 全プロセスの時間を進めてから同期操作を処理する
+		// 時間を進める前に起床させると、経過時間が起床後の状態で計上されてしまう
+		for proc in self.procs.iter_mut() {
+			proc.go(cpu_time, elapse);
+		}
+		for idx in 0..self.procs.len() {
+			// ミューテックス操作
+			if !self.mutexes.is_empty() {
+				self.check_mutex_point(idx, cpu_time);
+			}
+			// 同期操作
+			if !self.syncs.is_empty() {
+				self.check_sync_point(idx, cpu_time);
+			}
+		}
+		// 起動トリガ
+		self.check_trigger(cpu_time);
+		// アラーム/スケジュールテーブル
+		if !self.alarms.is_empty() {
+			self.check_alarm(cpu_time);
+		}
+	}
+
+	/**
+	アラーム/スケジュールテーブル満了チェック
+	cpu_timeまでに満了した満了点の起動先プロセスを起動、イベントをセットする
+	 */
+	fn check_alarm(&mut self, cpu_time:i32) {
+		for alarm_idx in 0..self.alarms.len() {
+			for action in self.alarms[alarm_idx].expire(cpu_time) {
+				match action {
+					AlarmAction::ACTIVATE(idx) => self.procs[idx].activate(cpu_time, vec![]),
+					AlarmAction::SETEVENT(name) => self.post_sync(&name, cpu_time),
+				}
+			}
+		}
+	}
+
+	/**
+	起動トリガチェック
+	時間経過で発生した起動トリガにより起動先プロセスを起動し、チェーンの完了を集計する
+	 */
+	fn check_trigger(&mut self, cpu_time:i32) {
+		let mut events = vec![];
+		for proc in self.procs.iter_mut() {
+			events.append(&mut proc.take_trigger_events());
+			if let Some(job_chain) = proc.take_job_done() {
+				for chain in self.chains.iter_mut() {
+					chain.check(proc.id, &job_chain, cpu_time);
+				}
+				// 拡張タスクのイベントはジョブ完了でクリア
+				for object in self.syncs.values_mut() {
+					if object.owner == Some(proc.id as usize) {
+						object.count = 0;
+					}
+				}
+			}
+		}
+		for (idx, job_chain) in events {
+			self.procs[idx].activate(cpu_time, job_chain);
+		}
+	}
+
+	/**
+	プロセス名からプロセスidxを取得
+	 */
+	fn get_proc_idx(&self, name: &str) -> Option<usize> {
+		self.procs.iter().position(|proc| proc.name == name)
+	}
+
+	/**
+	アクティブプロセスのミューテックス操作
+	いずれかのコアのアクティブプロセスがミューテックス待ちになったらtrueを返す
+	 */
+	fn check_active_mutex(&mut self, cpu_time:i32) -> bool {
+		if self.mutexes.is_empty() {
+			return false;
+		}
+		let mut blocked = false;
+		for core in 0..self.config.core_num {
+			if let Some(_active_proc_idx) = self.active_proc_idx[core] {
+				if self.check_mutex_point(_active_proc_idx, cpu_time) {
+					blocked = true;
+				}
+			}
+		}
+		blocked
+	}
+
+	/**
+	ミューテックス操作
+	プロセスの処理時間がロック/アンロック時点に達していれば操作を実施する
+	ミューテックス待ちになったらtrueを返す
+	 */
+	fn check_mutex_point(&mut self, idx: usize, cpu_time:i32) -> bool {
+		let mut changed = false;
+		let mut blocked = false;
+		// ジョブ完了で解放されたミューテックスを引き渡す
+		for name in self.procs[idx].take_released_mutexes() {
+			self.release_mutex(&name, cpu_time);
+			changed = true;
+		}
+		// ロック/アンロック要求
+		while let Some(request) = self.procs[idx].get_mutex_request() {
+			changed = true;
+			match request {
+				MutexRequest::LOCK(section) => {
+					let name = self.procs[idx].get_mutex_name(section).clone();
+					let state = self.mutexes.get_mut(&name).unwrap();
+					match state.owner {
+						None => {
+							// 空いていれば獲得
+							state.owner = Some(idx);
+							self.procs[idx].lock_mutex(section);
+						},
+						Some(owner) => {
+							// 他プロセスが占有中であればミューテックス待ち
+							state.waiters.push(idx);
+							let blocker = self.get_inversion_blocker(idx, owner);
+							self.procs[idx].block(cpu_time, blocker);
+							blocked = true;
+							break;
+						}
+					}
+				},
+				MutexRequest::UNLOCK(section) => {
+					let name = self.procs[idx].get_mutex_name(section).clone();
+					self.procs[idx].unlock_mutex(section);
+					self.release_mutex(&name, cpu_time);
+				},
+			}
+		}
+		// 占有状況が変わったら優先度継承を更新
+		if changed {
+			self.update_inherit_priority();
+		}
+		blocked
+	}
+
+	/**
+	アクティブプロセスの同期操作
+	いずれかのコアのアクティブプロセスが同期待ちになったらtrueを返す
+	 */
+	fn check_active_sync(&mut self, cpu_time:i32) -> bool {
+		if self.syncs.is_empty() {
+			return false;
+		}
+		let mut waited = false;
+		for core in 0..self.config.core_num {
+			if let Some(_active_proc_idx) = self.active_proc_idx[core] {
+				if self.check_sync_point(_active_proc_idx, cpu_time) {
+					waited = true;
+				}
+			}
+		}
+		waited
+	}
+
+	/**
+	同期操作
+	プロセスの処理時間が同期ポイントに達していれば送信/受信、セット/待ちを実施する
+	同期待ちになったらtrueを返す
+	 */
+	fn check_sync_point(&mut self, idx: usize, cpu_time:i32) -> bool {
+		while let Some(point_idx) = self.procs[idx].get_sync_request() {
+			let point = self.procs[idx].get_sync_points()[point_idx].clone();
+			let object = self.syncs.get_mut(&point.object).unwrap();
+			match point.op {
+				SyncOp::POST => {
+					self.procs[idx].done_sync(point_idx);
+					self.post_sync(&point.object, cpu_time);
+				},
+				SyncOp::TAKE => {
+					if object.count > 0 {
+						// データ/フラグがあれば受け取って継続
+						self.procs[idx].done_sync(point_idx);
+						object.count -= 1;
+						object.cnt_take += 1;
+					} else {
+						// 無ければ同期待ち
+						object.waiters.push_back(idx);
+						self.procs[idx].wait_event(cpu_time);
+						return true;
+					}
+				},
+			}
+		}
+		false
+	}
+
+	/**
+	送信/セット
+	待機プロセスがいれば起床し、いなければデータ/フラグ/イベントを保持する
+	 */
+	fn post_sync(&mut self, name: &str, cpu_time:i32) {
+		let object = self.syncs.get_mut(name).unwrap();
+		object.cnt_post += 1;
+		match object.kind {
+			SyncKind::QUEUE => {
+				// 待機プロセスがいれば先頭のプロセスに渡し、いなければキューに格納
+				match object.waiters.pop_front() {
+					Some(waiter) => {
+						object.cnt_take += 1;
+						self.procs[waiter].release_event(cpu_time);
+					},
+					None => {
+						object.count += 1;
+						object.max_count = object.max_count.max(object.count);
+					}
+				}
+			},
+			SyncKind::FLAG | SyncKind::EVENT => {
+				// 休止中タスクへのイベントセットは無視
+				if let Some(owner) = object.owner {
+					if self.procs[owner].is_waiting() {
+						return;
+					}
+				}
+				// 待機プロセスがいれば全て起床してクリア、いなければセット状態を保持
+				if object.waiters.is_empty() {
+					object.count = 1;
+					object.max_count = 1;
+				}
+				while let Some(waiter) = object.waiters.pop_front() {
+					object.cnt_take += 1;
+					self.procs[waiter].release_event(cpu_time);
+				}
+			},
+		}
+	}
+
+	/**
+	ミューテックス解放
+	待機プロセスがいれば最も優先度の高いプロセスに引き渡す
+	 */
+	fn release_mutex(&mut self, name: &str, cpu_time:i32) {
+		let state = self.mutexes.get_mut(name).unwrap();
+		state.owner = None;
+		// 待機プロセスから引き渡し先を選択(同優先度は待機順)
+		let mut next: Option<usize> = None;
+		for (pos, &waiter) in state.waiters.iter().enumerate() {
+			match next {
+				Some(_next) => {
+					if self.procs[waiter].get_cur_priority() > self.procs[state.waiters[_next]].get_cur_priority() {
+						next = Some(pos);
+					}
+				},
+				None => {
+					next = Some(pos);
+				}
+			}
+		}
+		if let Some(_next) = next {
+			let owner = state.waiters.remove(_next);
+			state.owner = Some(owner);
+			let waiters = state.waiters.clone();
+			// 引き渡し先はロックしてREADYへ
+			if let Some(section) = self.procs[owner].get_lock_pending() {
+				self.procs[owner].lock_mutex(section);
+			}
+			self.procs[owner].unblock(cpu_time);
+			// 残りの待機プロセスはブロック元が変わる
+			for waiter in waiters {
+				let blocker = self.get_inversion_blocker(waiter, owner);
+				self.procs[waiter].set_blocker(cpu_time, blocker);
+			}
+		}
+	}
+
+	/**
+	優先度逆転判定
+	ミューテックス待ちプロセスより占有プロセスの優先度が低ければ占有プロセスのIDを返す
+	 */
+	fn get_inversion_blocker(&self, waiter: usize, owner: usize) -> Option<i32> {
+		if self.procs[owner].priority < self.procs[waiter].priority {
+			Some(self.procs[owner].id)
+		} else {
+			None
+		}
+	}
+
+	/**
+	優先度継承更新
+	ミューテックス占有プロセスは待機プロセスの最高優先度を継承する
+	 */
+	fn update_inherit_priority(&mut self) {
+		for proc in self.procs.iter_mut() {
+			proc.set_inherit_priority(None);
+		}
+		let links: Vec<(usize, Vec<usize>)> = self.mutexes.values()
+			.filter_map(|state| state.owner.map(|owner| (owner, state.waiters.clone())))
+			.collect();
+		// 継承の連鎖を考慮して変化がなくなるまで繰り返す
+		let mut changed = true;
+		while changed {
+			changed = false;
+			for (owner, waiters) in links.iter() {
+				for waiter in waiters.iter() {
+					let priority = self.procs[*waiter].get_cur_priority();
+					if priority > self.procs[*owner].get_cur_priority() {
+						self.procs[*owner].set_inherit_priority(Some(priority));
+						changed = true;
+					}
+				}
+			}
+		}
+	}
+
+	fn check_cpu_use(&mut self, elapse:i32) {
+		let mut busy = 0;
+		for core in 0..self.config.core_num {
+			match &mut self.active_proc_idx[core] {
+				Some(_idx) => {
+					self.cpu_use_busy[core] += elapse;
+					busy += elapse;
+				},
+				None => {
+					self.cpu_use_idle[core] += elapse;
+				}
+			}
+		}
+		if let Some(mode) = self.modes.get_mut(self.mode_cur) {
+			mode.go(elapse, busy);
+		}
+		if let Some((_, duration, freq_busy)) = self.freq_stats.get_mut(self.freq_cur) {
+			*duration += elapse;
+			*freq_busy += busy;
+		}
+	}
+
+	/**
+	周波数切り替えチェック
+	切り替え時刻に達していれば各プロセスの処理時間を新しい周波数で換算する
+	 */
+	fn check_freq(&mut self, cpu_time:i32) {
+		while let Some(&(freq, time)) = self.config.freq_changes.get(self.freq_change_idx) {
+			if time > cpu_time {
+				break;
+			}
+			self.freq_change_idx += 1;
+			self.freq_cur = match self.freq_stats.iter().position(|(_freq, _, _)| *_freq == freq) {
+				Some(idx) => idx,
+				None => {
+					self.freq_stats.push((freq, 0, 0));
+					self.freq_stats.len() - 1
+				}
+			};
+			for proc in self.procs.iter_mut() {
+				proc.set_cpu_freq(freq);
+			}
+		}
+	}
+
+	/**
+	モード切り替えチェック
+	切り替え時刻に達していれば現在モードを集計し、各プロセスに新しいモードのパラメータを適用する
+	 */
+	fn check_mode(&mut self, cpu_time:i32) {
+		while let Some((name, time)) = self.config.mode_changes.get(self.mode_change_idx) {
+			if *time > cpu_time {
+				break;
+			}
+			let name = name.clone();
+			self.mode_change_idx += 1;
+			self.leave_mode();
+			self.mode_cur = self.modes.iter().position(|mode| mode.name == name).unwrap();
+			self.modes[self.mode_cur].enter();
+			for proc in self.procs.iter_mut() {
+				proc.change_mode(cpu_time, &name);
+			}
+		}
+	}
+
+	/**
+	現在モードのプロセス集計
+	 */
+	fn leave_mode(&mut self) {
+		for (idx, proc) in self.procs.iter_mut().enumerate() {
+			let (overrun, _, _) = proc.get_delay_count();
+			let max_rate = proc.take_mode_max_cpu_use_rate();
+			self.modes[self.mode_cur].add_proc(idx, max_rate, overrun - self.mode_overrun_base[idx]);
+			self.mode_overrun_base[idx] = overrun;
+		}
+	}
+
+	pub fn output_proc_result(&mut self) {
+		// キャプション出力
+		println!("[{:40}] | CPU-userate (occur point  ) | overrun | DL-miss |   max-late", "Process Name");
+		println!("-{0:-<40}--+-{0:-<27}-+-{0:-<7}-+-{0:-<7}-+-{0:-<10}-", "");
+		// 各プロセス情報出力
+		for proc in self.procs.iter() {
+			println!("{}", proc);
+		}
+		// カーネル擬似プロセス出力
+		if let Some(kernel) = self.kernel.as_ref() {
+			// 全体CPU使用率と同じくトレース時間(全コア合計)に対する割合とする
+			println!("{}", kernel.get_result(self.trace_time * self.config.core_num as i32));
+		}
+		// 全体CPU使用率出力
+		let delay = if self.cpu_use_rate > 100.0 {
+			"(delayed!)"
+		} else {
+			""
+		};
+		println!("-{0:-<40}--+-{0:-<27}-+-{0:-<7}-+-{0:-<7}-+-{0:-<10}-", "");
+		// マルチコア時はコア毎のCPU使用率を出力
+		if self.config.core_num > 1 {
+			for core in 0..self.config.core_num {
+				let runtime = (self.cpu_use_busy[core] + self.cpu_use_idle[core]) as f32;
+				let rate = self.cpu_use_busy[core] as f32 / runtime * 100.0;
+				println!("[{:40}] | {:10.2}% {:15} | {:7} | {:7} | {:10}", format!("core{}", core), rate, "", "", "", "");
+			}
+		}
+		println!("[{:40}] | {:10.2}% {:15} | {:7} | {:7} | {:10} {}", "all", self.cpu_use_rate, "", "", "", "", delay);
+		// ブロッキング結果出力
+		if self.use_resource || !self.mutexes.is_empty() {
+			println!();
+			println!("[{:40}] | max-block  | total-block | inversion | max-invert", "Process Name");
+			println!("-{0:-<40}--+-{0:-<10}-+-{0:-<11}-+-{0:-<9}-+-{0:-<10}-", "");
+			for proc in self.procs.iter() {
+				println!("{}", proc.get_blocking_result());
+			}
+		}
+		// プロセスチェーン結果出力
+		if !self.chains.is_empty() {
+			println!();
+			println!("[{:40}] | count   | min-latency | ave-latency | max-latency", "Chain Name");
+			println!("-{0:-<40}--+-{0:-<7}-+-{0:-<11}-+-{0:-<11}-+-{0:-<11}-", "");
+			for chain in self.chains.iter() {
+				println!("{}", chain.get_result());
+				println!("  {}", chain.procs.join(" -> "));
+			}
+		}
+		// アラーム/スケジュールテーブル結果出力
+		if !self.alarms.is_empty() {
+			println!();
+			println!("[{:40}] | kind       | expiry    | activate", "Alarm Name");
+			println!("-{0:-<40}--+-{0:-<10}-+-{0:-<9}-+-{0:-<9}-", "");
+			for alarm in self.alarms.iter() {
+				println!("{}", alarm.get_result());
+			}
+		}
+		// 同期待ち結果出力
+		if !self.syncs.is_empty() {
+			println!();
+			println!("[{:40}] | ev-wait | max-wait   | total-wait", "Process Name");
+			println!("-{0:-<40}--+-{0:-<7}-+-{0:-<10}-+-{0:-<10}-", "");
+			for proc in self.procs.iter() {
+				println!("{}", proc.get_evwait_result());
+			}
+			println!();
+			println!("[{:40}] | kind  | post      | take      | max-count", "Object Name");
+			println!("-{0:-<40}--+-{0:-<5}-+-{0:-<9}-+-{0:-<9}-+-{0:-<9}-", "");
+			let mut names: Vec<&String> = self.syncs.keys().collect();
+			names.sort();
+			for name in names {
+				println!("{}", self.syncs[name].get_result(name));
+			}
+		}
+		// 割り込みレイテンシ結果出力
+		if self.use_intlock {
+			println!();
+			println!("[{:40}] | level | max-latency | max-masked | total-masked", "Process Name");
+			println!("-{0:-<40}--+-{0:-<5}-+-{0:-<11}-+-{0:-<10}-+-{0:-<12}-", "");
+			for proc in self.procs.iter() {
+				if let Some(result) = proc.get_latency_result() {
+					println!("{}", result);
+				}
+			}
+		}
+		// カーネルオーバーヘッド結果出力
+		if let Some(kernel) = self.kernel.as_ref() {
+			println!();
+			println!("[{:40}] | task-switch | isr-entry | total-time", "Process Name");
+			println!("-{0:-<40}--+-{0:-<11}-+-{0:-<9}-+-{0:-<10}-", "");
+			println!("{}", kernel.get_count_result());
+		}
+		// マイグレーション結果出力
+		if let CoreAssign::GLOBAL = self.config.core_assign {
+			println!();
+			println!("[{:40}] | migration", "Process Name");
+			println!("-{0:-<40}--+-{0:-<9}-", "");
+			for proc in self.procs.iter() {
+				println!("{}", proc.get_migration_result());
+			}
+		}
+		// モード別結果出力
+		if !self.modes.is_empty() {
+			println!();
+			println!("[{:40}] | enter   | duration   | CPU-userate", "Mode Name");
+			println!("-{0:-<40}--+-{0:-<7}-+-{0:-<10}-+-{0:-<11}-", "");
+			for mode in self.modes.iter() {
+				println!("{}", mode.get_result(self.config.core_num));
+			}
+			println!();
+			println!("[{:40}] | CPU-userate | overrun", "Mode Name/Process Name");
+			println!("-{0:-<40}--+-{0:-<11}-+-{0:-<7}-", "");
+			for mode in self.modes.iter() {
+				for (idx, proc) in self.procs.iter().enumerate() {
+					println!("{}", mode.get_proc_result(idx, &proc.name));
+				}
+			}
+		}
+		// 周波数別結果出力
+		if !self.freq_stats.is_empty() {
+			println!();
+			println!("[{:40}] | duration   | CPU-userate", "CPU Frequency");
+			println!("-{0:-<40}--+-{0:-<10}-+-{0:-<11}-", "");
+			for (freq, duration, busy) in self.freq_stats.iter() {
+				let userate = match duration {
+					0 => 0.0,
+					_ => *busy as f32 / (*duration as f32 * self.config.core_num as f32) * 100.0,
+				};
+				println!("[{:40}] | {:10} | {:10.2}%", format!("{}MHz", freq), duration, userate);
+			}
+		}
+		// 処理時間分布結果出力
+		let exec_results: Vec<String> = self.procs.iter().filter_map(|proc| proc.get_exec_result()).collect();
+		if !exec_results.is_empty() {
+			println!();
+			println!("[{:40}] | mode    | count   | min-exec  | ave-exec  | max-exec", "Process Name");
+			println!("-{0:-<40}--+-{0:-<7}-+-{0:-<7}-+-{0:-<9}-+-{0:-<9}-+-{0:-<9}-", "");
+			for result in exec_results.iter() {
+				println!("{}", result);
+			}
+		}
+		// 多重起動結果出力
+		let act_results: Vec<String> = self.procs.iter().filter_map(|proc| proc.get_activation_result()).collect();
+		if !act_results.is_empty() {
+			println!();
+			println!("[{:40}] | queued  | dropped | dropped activation time", "Process Name");
+			println!("-{0:-<40}--+-{0:-<7}-+-{0:-<7}-+-{0:-<24}-", "");
+			for result in act_results.iter() {
+				println!("{}", result);
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+	use super::*;
+	use crate::process::process_alarm::AlarmKind;
+	use crate::process::process_montecarlo::MonteCarloStats;
+	use crate::process::process_callback::ProcessLog;
+	use crate::process::process_option::ProcessOption;
+	use crate::process::process_state::ProcessState;
+	use crate::profiler::profiler::PlantUML;
+	use crate::settings::Settings;
+
+	type Log = Vec<(String, i32, String, i32, i32, bool)>;
+	type Records = Vec<(String, i32, ProcessLog, i32, i32, bool)>;
+	type Cb<'a> = &'a dyn Fn(&String, i32, ProcessLog, i32, i32, bool);
+
+	/**
+	設定ファイルの[ProcessInfo]形式のプロセス定義をトレースし、コールバック列を返す
+	tick_step: trueで1tick毎に時間を進める, inspectでトレース後のProcessTracerから結果を取り出す
+	*/
+	fn run_lines<R>(lines: &[&str], config: TraceConfig, trace_time: i32, tick_step: bool, inspect: impl FnOnce(&ProcessTracer<Cb>) -> R) -> (Log, R) {
+		let log: RefCell<Log> = RefCell::new(vec![]);
+		let push = |name: &String, id: i32, record: ProcessLog, begin: i32, end: i32, delayed: bool| {
+			log.borrow_mut().push((name.clone(), id, record.to_string(), begin, end, delayed));
+		};
+		let cb: Cb = &push;
+		let mut procs = vec![];
+		let mut settings = Settings::new();
+		for line in lines.iter() {
+			settings.load_process(line, &mut |kind, name, state, pri, enable, cycle, time, option| {
+				procs.push(Process::new(kind, name, state, pri, enable, cycle, time, option, cb));
+			});
+		}
+		let mut tracer = ProcessTracer::new(procs, config, cb);
+		tracer.trace(trace_time, tick_step);
+		let result = inspect(&tracer);
+		drop(tracer);
+		(log.into_inner(), result)
+	}
+
+	/**
+	結果出力の各欄(プロセス名を除く)を取り出す
+	*/
+	fn fields(result: &str) -> Vec<String> {
+		result.split('|').skip(1).map(|field| field.trim().to_string()).collect()
+	}
+
+	/**
+	プロセスのコールバック列から指定状態の区間(begin, end)を取り出す
+	*/
+	fn spans(log: &Log, name: &str, state: &str) -> Vec<(i32, i32)> {
+		log.iter().filter(|rec| rec.0 == name && rec.2 == state).map(|rec| (rec.3, rec.4)).collect()
+	}
+
+	fn make_config(task_use_preempt: bool, overhead: i32) -> TraceConfig {
+		TraceConfig {
+			task_use_preempt,
+			overhead_task_switch: overhead,
+			overhead_isr_entry: overhead,
+			overhead_isr_exit: overhead,
+			overhead_scheduler: overhead,
+			..Default::default()
+		}
+	}
+
+	/**
+	小さなプロセス構成をトレースし、コールバック列とプロセス毎の結果を返す
+	*/
+	fn trace(task_use_preempt: bool, overhead: i32, tick_step: bool) -> (Log, Vec<String>) {
+		let log: RefCell<Log> = RefCell::new(vec![]);
+		let cb = |name: &String, id: i32, record: ProcessLog, begin: i32, end: i32, delayed: bool| {
+			log.borrow_mut().push((name.clone(), id, record.to_string(), begin, end, delayed));
+		};
+		let offset = ProcessOption { time_offset: Some(7), ..Default::default() };
+		let procs = vec![
+			Process::intr("isr".to_string(), ProcessState::WAITING, 100, false, 50, vec![4, 2], ProcessOption::default(), cb),
+			Process::task("t1".to_string(), ProcessState::WAITING, 10, false, 100, vec![30, 20, 45], offset, cb),
+			Process::task("t2".to_string(), ProcessState::WAITING, 5, false, 200, vec![90, 60], ProcessOption::default(), cb),
+		];
+		let mut tracer = ProcessTracer::new(procs, make_config(task_use_preempt, overhead), cb);
+		tracer.trace(2000, tick_step);
+		let results = tracer.procs.iter().map(|proc| proc.to_string()).collect();
+		drop(tracer);
+		(log.into_inner(), results)
+	}
+
+	#[test]
+	fn event_step_matches_tick_step() {
+		for (task_use_preempt, overhead) in [(true, 0), (false, 0), (true, 2)] {
+			let (event_log, event_results) = trace(task_use_preempt, overhead, false);
+			let (tick_log, tick_results) = trace(task_use_preempt, overhead, true);
+			assert!(!event_log.is_empty());
+			assert_eq!(event_log, tick_log);
+			assert_eq!(event_results, tick_results);
+		}
+	}
+
+	/**
+	test/以下の設定ファイルをトレースし、PlantUML出力と結果出力の(プロセス名, CPU使用率)欄を返す
+	configは設定ファイルの[TraceInfo]と同じ設定を指定する
+	*/
+	fn trace_file(file_stem: &str, config: TraceConfig, trace_time: i32) -> (String, Vec<(String, String)>) {
+		let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test");
+		let log: RefCell<Records> = RefCell::new(vec![]);
+		let push = |name: &String, id: i32, record: ProcessLog, begin: i32, end: i32, delayed: bool| {
+			log.borrow_mut().push((name.clone(), id, record, begin, end, delayed));
+		};
+		let cb: Cb = &push;
+		let mut procs = vec![];
+		Settings::new().load(&dir.join(format!("{}.txt", file_stem)).to_string_lossy().to_string(), &mut |kind, name, state, pri, enable, cycle, time, option| {
+			procs.push(Process::new(kind, name, state, pri, enable, cycle, time, option, cb));
+		}).unwrap();
+		let mut tracer = ProcessTracer::new(procs, config, cb);
+		// PlantUMLは一時ディレクトリに出力して読み出す
+		let out_base = std::env::temp_dir().join(format!("cpu_usage_golden_{}", file_stem));
+		let mut pu = PlantUML::new(&out_base.to_string_lossy(), 0, trace_time);
+		pu.make_header(&tracer.procs, tracer.kernel.as_ref());
+		tracer.run(trace_time);
+		let mut results: Vec<(String, String)> = tracer.procs.iter().map(|proc| {
+			let result = proc.to_string();
+			let fields: Vec<&str> = result.split(" | ").collect();
+			(fields[0].trim().to_string(), fields[1].trim().to_string())
+		}).collect();
+		results.push((format!("[{:40}]", "all"), format!("{:.2}%", tracer.cpu_use_rate)));
+		drop(tracer);
+		pu.start().unwrap();
+		for (name, id, record, begin, end, delayed) in log.into_inner() {
+			pu.profile(&name, id, record, begin, end, delayed);
+		}
+		pu.finish();
+		let out_path = out_base.with_extension("plantuml");
+		let plantuml = std::fs::read_to_string(&out_path).unwrap();
+		std::fs::remove_file(&out_path).unwrap();
+		(plantuml, results)
+	}
+
+	/**
+	改修前の実装で出力したPlantUML/結果出力(test/golden/)と一致すること
+	*/
+	#[test]
+	fn trace_matches_golden_output() {
+		let files = [
+			("test_process_info_2", TraceConfig::default()),
+			("test_process_info_3", TraceConfig { task_use_preempt: false, ..Default::default() }),
+		];
+		let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test/golden");
+		for (file_stem, config) in files {
+			let (plantuml, results) = trace_file(file_stem, config, 6000);
+			let golden_plantuml = std::fs::read_to_string(dir.join(format!("{}.plantuml", file_stem))).unwrap();
+			assert_eq!(plantuml, golden_plantuml, "{}", file_stem);
+			// 結果出力はプロセス名とCPU使用率(発生時刻)欄を比較する
+			let golden_result = std::fs::read_to_string(dir.join(format!("{}.result", file_stem))).unwrap();
+			let golden_results: Vec<(String, String)> = golden_result.lines()
+				.filter(|line| line.starts_with('[') && !line.starts_with("[Process Name"))
+				.map(|line| {
+					let fields: Vec<&str> = line.split(" | ").collect();
+					(fields[0].trim().to_string(), fields[1].trim().to_string())
+				})
+				.collect();
+			assert_eq!(results, golden_results, "{}", file_stem);
+		}
+	}
+
+	/**
+	下位idxのプロセスが送信したデータで上位idxのプロセスが起床する場合も
+	待ち時間がEVWAITとして計上されること
+	*/
+	#[test]
+	fn evwait_does_not_depend_on_process_order() {
+		let prod = "prod TASK WAITING 5 enable 200 60 send=q:45";
+		let cons = "cons TASK WAITING 10 enable 200 20 recv=q:10";
+		for lines in [[prod, cons], [cons, prod]] {
+			for tick_step in [false, true] {
+				let (log, result) = run_lines(&lines, TraceConfig::default(), 400, tick_step, |tracer| {
+					let idx = tracer.get_proc_idx("cons").unwrap();
+					tracer.procs[idx].get_evwait_result()
+				});
+				// consは210で受信待ち、prodが255で送信
+				assert_eq!(fields(&result), ["1", "45", "45"], "order={:?} tick_step={}", lines, tick_step);
+				assert_eq!(spans(&log, "cons", "EVWAIT")[0], (210, 255));
+			}
+		}
+	}
+
+	/**
+	下位idxのタスクがセットしたイベントで拡張タスクが起床する場合も
+	待ち時間がEVWAITとして計上されること
+	*/
+	#[test]
+	fn event_wait_does_not_depend_on_process_order() {
+		let setter = "setter TASK WAITING 5 enable 200 60 setevent=ext.ev:45";
+		let ext = "ext TASK WAITING 10 enable 200 20 waitevent=ev:10";
+		for lines in [[setter, ext], [ext, setter]] {
+			for tick_step in [false, true] {
+				let (log, result) = run_lines(&lines, TraceConfig::default(), 400, tick_step, |tracer| {
+					let idx = tracer.get_proc_idx("ext").unwrap();
+					tracer.procs[idx].get_evwait_result()
+				});
+				// extは210でイベント待ち、setterが255でセット
+				assert_eq!(fields(&result), ["1", "45", "45"], "order={:?} tick_step={}", lines, tick_step);
+				assert_eq!(spans(&log, "ext", "EVWAIT")[0], (210, 255));
+			}
+		}
+	}
+
+	/**
+	[ProcessInfo]の1行を解析し、(プロセス名, 処理時間欄)を返す
+	*/
+	fn parse_line(line: &str) -> Vec<(String, Vec<i32>)> {
+		let mut result = vec![];
+		Settings::new().load_process(line, &mut |_kind, name, _state, _pri, _enable, _cycle, time, _option| {
+			result.push((name, time));
+		});
+		result
+	}
+
+	#[test]
+	fn process_line_is_parsed_from_the_beginning() {
+		assert_eq!(parse_line("p1 TASK WAITING 1 enable 100 50 40 quantum=5  "), [("p1".to_string(), vec![50, 40])]);
+		// 状態欄の無い旧形式は解析対象外としてスキップ
+		assert!(parse_line("proc1			INTR		2		disable		500				100		20").is_empty());
+	}
+
+	#[test]
+	#[should_panic(expected = "processing time is required in p1")]
+	fn process_line_requires_time_without_time_file() {
+		parse_line("p1 TASK WAITING 1 enable 100 quantum=5");
+	}
+
+	#[test]
+	#[should_panic(expected = "cannot parse '50 quantum=5 60' in p1")]
+	fn process_line_rejects_unparsed_tokens() {
+		parse_line("p1 TASK WAITING 1 enable 100 50 quantum=5 60");
+	}
+
+	/**
+	割り込みレベル使用時のINTR同士は優先度によらず高レベルのみ多重割り込みすること
+	*/
+	#[test]
+	fn intr_level_is_independent_of_priority() {
+		// loは優先度が高いがレベルが低い
+		let lines = ["lo INTR WAITING 30 enable 300 30 level=1 offset=100", "hi INTR WAITING 10 enable 300 10 level=2 offset=110"];
+		let (log, _) = run_lines(&lines, TraceConfig::default(), 300, false, |_| ());
+		assert_eq!(spans(&log, "hi", "RUNNING"), [(111, 120)]);
+		assert_eq!(spans(&log, "lo", "RUNNING"), [(101, 111), (121, 140)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid level: hi has no level while other INTR has")]
+	fn intr_level_is_required_on_every_intr() {
+		let lines = ["lo INTR WAITING 10 enable 300 30 level=1", "hi INTR WAITING 20 enable 300 10"];
+		run_lines(&lines, TraceConfig::default(), 300, false, |_| ());
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid level: t1 is not INTR")]
+	fn intr_level_is_rejected_on_task() {
+		let lines = ["lo INTR WAITING 10 enable 300 30 level=1", "t1 TASK WAITING 5 enable 300 10 level=2"];
+		run_lines(&lines, TraceConfig::default(), 300, false, |_| ());
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid intlock: LEVEL in t1 requires level of INTR")]
+	fn intlock_level_requires_intr_level() {
+		let lines = ["isr INTR WAITING 10 enable 300 30", "t1 TASK WAITING 5 enable 300 50 intlock=0:10:1"];
+		run_lines(&lines, TraceConfig::default(), 300, false, |_| ());
+	}
+
+	/**
+	設定ファイルを一時ファイルに書き出して読み込み、プロセス名の一覧を返す
+	*/
+	/**
+	一時ファイル(テスト中のpanicでも削除する)
+	*/
+	struct TempFile(std::path::PathBuf);
+	impl TempFile {
+		fn new(file_name: &str, text: &str) -> TempFile {
+			let file = TempFile(std::env::temp_dir().join(file_name));
+			std::fs::write(&file.0, text).unwrap();
+			file
+		}
+	}
+	impl Drop for TempFile {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_file(&self.0);
+		}
+	}
+	fn load_text(file_name: &str, text: &str) -> Vec<String> {
+		let file = TempFile::new(file_name, text);
+		let mut names = vec![];
+		Settings::new().load(&file.0.to_string_lossy().to_string(), &mut |_kind, name, _state, _pri, _enable, _cycle, _time, _option| {
+			names.push(name);
+		}).unwrap();
+		names
+	}
+
+	#[test]
+	fn mode_change_accepts_default_without_mode_param() {
+		let text = "[TraceInfo]\nModeChange=diag:100,default:200\n[ProcessInfo]\np1 TASK WAITING 1 enable 100 50 mode.diag.cycle=50\n";
+		assert_eq!(load_text("cpu_usage_mode_ok.txt", text), ["p1"]);
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid ModeChange: diag has no mode.diag.* in ProcessInfo")]
+	fn mode_change_requires_mode_param() {
+		let text = "[TraceInfo]\nModeChange=diag:100\n[ProcessInfo]\np1 TASK WAITING 1 enable 100 50\n";
+		load_text("cpu_usage_mode_unused.txt", text);
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid mode: diag is not in ModeChange")]
+	fn mode_param_requires_mode_change() {
+		let text = "[ProcessInfo]\np1 TASK WAITING 1 enable 100 50 mode.diag.cycle=50\n";
+		load_text("cpu_usage_mode_unknown.txt", text);
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid ProcessOption: exec_mode=uniform requires bcet in p1")]
+	fn exec_mode_requires_bcet() {
+		parse_line("p1 TASK WAITING 1 enable 100 50 exec_mode=uniform");
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid ProcessOption: bcet=60 exceeds WCET 50 in p1")]
+	fn bcet_must_not_exceed_wcet() {
+		parse_line("p1 TASK WAITING 1 enable 100 50 exec_mode=normal bcet=60");
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid ProcessOption: bcet=30 exceeds WCET 20 in p1")]
+	fn bcet_must_not_exceed_mode_wcet() {
+		parse_line("p1 TASK WAITING 1 enable 100 50 exec_mode=uniform bcet=30 mode.diag.time=20");
+	}
+
+	#[test]
+	#[should_panic(expected = "kernel is reserved for the kernel pseudo-process")]
+	fn process_name_kernel_is_reserved() {
+		parse_line("kernel TASK WAITING 1 enable 100 50");
+	}
+
+	/**
+	名前を指定してプロセスを取り出す
+	*/
+	fn proc_of<'a, 'b>(tracer: &'a ProcessTracer<Cb<'b>>, name: &str) -> &'a Process<Cb<'b>> {
+		&tracer.procs[tracer.get_proc_idx(name).unwrap()]
+	}
+
+	/**
+	EDFでは優先度によらず絶対デッドラインの近いTASKを選択し、
+	デッドラインの近いジョブが到着すれば実行中のTASKをpreemptすること
+	*/
+	#[test]
+	fn edf_selects_earliest_deadline() {
+		// aは低優先度だがデッドラインが短い
+		let lines = ["a TASK WAITING 1 enable 100 30 offset=50 deadline=60", "b TASK WAITING 2 enable 300 150 offset=1"];
+		let config = TraceConfig { task_policy: SchedPolicy::EDF, ..Default::default() };
+		let (log, delay) = run_lines(&lines, config, 300, false, |tracer| proc_of(tracer, "a").get_delay_count());
+		assert_eq!(spans(&log, "a", "RUNNING"), [(51, 80), (151, 180), (251, 280)]);
+		assert_eq!(spans(&log, "b", "RUNNING"), [(2, 51), (81, 151), (181, 211)]);
+		assert_eq!(delay, (0, 0, Some(-30)));
+		// 固定優先度ではaがbの完了まで待たされてデッドラインミスする
+		let (_, delay) = run_lines(&lines, TraceConfig::default(), 300, false, |tracer| proc_of(tracer, "a").get_delay_count());
+		assert_eq!(delay, (1, 2, Some(71)));
+	}
+
+	/**
+	同優先度のTASKはタイムクォンタム毎に到着順に切り替わり、個別設定のquantumが優先されること
+	*/
+	#[test]
+	fn round_robin_rotates_equal_priority_tasks() {
+		let lines = ["a TASK WAITING 1 enable 200 25 offset=10", "b TASK WAITING 1 enable 200 25 offset=10", "c TASK WAITING 1 enable 200 10 offset=10 quantum=5"];
+		for tick_step in [false, true] {
+			let config = TraceConfig { time_quantum: 10, ..Default::default() };
+			let (log, _) = run_lines(&lines, config, 200, tick_step, |_| ());
+			assert_eq!(spans(&log, "a", "RUNNING"), [(11, 21), (36, 46), (61, 65)]);
+			assert_eq!(spans(&log, "b", "RUNNING"), [(21, 31), (46, 56), (66, 70)]);
+			assert_eq!(spans(&log, "c", "RUNNING"), [(31, 36), (56, 60)]);
+		}
+		// タイムクォンタム0では完了まで切り替わらない
+		let (log, _) = run_lines(&lines, TraceConfig::default(), 200, false, |_| ());
+		assert_eq!(spans(&log, "a", "RUNNING"), [(11, 35)]);
+		assert_eq!(spans(&log, "c", "RUNNING"), [(61, 70)]);
+	}
+
+	/**
+	相対デッドライン指定時は起動周期内に完了してもデッドライン超過をデッドラインミスとして計上すること
+	*/
+	#[test]
+	fn deadline_miss_is_separate_from_overrun() {
+		let hi = "hi TASK WAITING 2 enable 100 40 offset=10";
+		for tick_step in [false, true] {
+			let (_, result) = run_lines(&[hi, "lo TASK WAITING 1 enable 200 30 offset=10 deadline=50"], TraceConfig::default(), 400, tick_step, |tracer| proc_of(tracer, "lo").to_string());
+			// 起動10, デッドライン60に対して完了80
+			assert_eq!(fields(&result)[1..], ["0", "2", "20"]);
+		}
+		// デッドライン未指定時は起動周期をデッドラインとする
+		let (_, result) = run_lines(&[hi, "lo TASK WAITING 1 enable 200 30 offset=10"], TraceConfig::default(), 400, false, |tracer| proc_of(tracer, "lo").to_string());
+		assert_eq!(fields(&result)[1..], ["0", "0", "-130"]);
+	}
+
+	/**
+	初回起動時刻指定時はその時刻から起動周期で起動し、起動周期より後の時刻も指定できること
+	*/
+	#[test]
+	fn offset_shifts_first_release() {
+		let cases: [(&str, &[(i32, i32)]); 3] = [
+			("a TASK WAITING 1 enable 100 10", &[(100, 101), (200, 201)]),
+			("a TASK WAITING 1 enable 100 10 offset=30", &[(30, 31), (130, 131), (230, 231)]),
+			("a TASK WAITING 1 enable 100 10 offset=130", &[(130, 131), (230, 231)]),
+		];
+		for (line, ready) in cases {
+			for tick_step in [false, true] {
+				let (log, _) = run_lines(&[line], TraceConfig::default(), 300, tick_step, |_| ());
+				assert_eq!(spans(&log, "a", "READY"), ready, "{}", line);
+			}
+		}
+	}
+
+	/**
+	リリースジッタは起動周期の基準時刻からの遅れとして発生方法毎に適用され、
+	乱数はシードから再現できること
+	*/
+	#[test]
+	fn release_jitter_follows_mode_and_seed() {
+		let cases: [(&str, [i32; 5]); 3] = [
+			("a TASK WAITING 1 enable 100 10 offset=20 jitter=7", [27, 127, 227, 327, 427]),
+			("a TASK WAITING 1 enable 100 10 offset=20 jitter=7 jitter_mode=alt", [27, 120, 227, 320, 427]),
+			("a TASK WAITING 1 enable 100 10 offset=20 jitter=3,9", [23, 129, 223, 329, 423]),
+		];
+		let releases = |line: &str, seed: u64, tick_step: bool| {
+			let (log, _) = run_lines(&[line], TraceConfig { seed, ..Default::default() }, 500, tick_step, |_| ());
+			spans(&log, "a", "READY").iter().map(|span| span.0).collect::<Vec<i32>>()
+		};
+		for (line, expected) in cases {
+			for tick_step in [false, true] {
+				assert_eq!(releases(line, 0, tick_step), expected, "{}", line);
+			}
+		}
+		// 乱数ジッタは上限以内で、同じシードでは同じ系列になる
+		let random = "a TASK WAITING 1 enable 100 10 offset=20 jitter=30 jitter_mode=random";
+		let seed1 = releases(random, 1, false);
+		assert_eq!(seed1.len(), 5);
+		for (idx, release) in seed1.iter().enumerate() {
+			let base = 20 + 100 * idx as i32;
+			assert!(base <= *release && *release <= base + 30, "{:?}", seed1);
+		}
+		assert_eq!(releases(random, 1, true), seed1);
+		assert_ne!(releases(random, 2, false), seed1);
+	}
+
+	/**
+	スポラディック起動は最小到着間隔を守り、到着時刻リストの詰まった到着は後ろにずらすこと
+	*/
+	#[test]
+	fn sporadic_arrivals_keep_min_interval() {
+		let releases = |line: &str, tick_step: bool| {
+			let (log, _) = run_lines(&[line], TraceConfig { seed: 3, ..Default::default() }, 600, tick_step, |_| ());
+			spans(&log, "s", "READY").iter().map(|span| span.0).collect::<Vec<i32>>()
+		};
+		for tick_step in [false, true] {
+			assert_eq!(releases("s TASK WAITING 1 enable 100 10 sporadic=dense", tick_step), [100, 200, 300, 400, 500]);
+			// 80は30から最小到着間隔100経過後の130、310は400にずらす
+			assert_eq!(releases("s TASK WAITING 1 enable 100 10 sporadic=list arrivals=30,80,300,310", tick_step), [30, 130, 300, 400]);
+		}
+		// 乱数の到着間隔は[最小到着間隔, 最大到着間隔]
+		let random = releases("s TASK WAITING 1 enable 100 10 sporadic=random max_interval=150", false);
+		assert!(random.len() >= 3);
+		let mut prev = 0;
+		for release in random.iter() {
+			assert!(prev + 100 <= *release && *release <= prev + 150, "{:?}", random);
+			prev = *release;
+		}
+		assert_eq!(releases("s TASK WAITING 1 enable 100 10 sporadic=random max_interval=150", true), random);
+	}
+
+	/**
+	多重起動上限指定時は実行中に到達した起動要求を上限までキューイングし、
+	完了後に起動時刻の順で続けて実行し、超過分は破棄として記録すること
+	*/
+	#[test]
+	fn activation_queue_runs_pending_jobs() {
+		for tick_step in [false, true] {
+			let (log, (act, result)) = run_lines(&["a TASK WAITING 1 enable 100 180 offset=10 act_limit=2"], TraceConfig::default(), 600, tick_step, |tracer| {
+				(tracer.procs[0].get_activation_result().unwrap(), tracer.procs[0].to_string())
+			});
+			assert_eq!(spans(&log, "a", "RUNNING"), [(11, 190), (191, 370), (371, 550)]);
+			// 110, 210, 410をキューイング, 310と510は実行中と待ちの2件で上限のため破棄
+			assert_eq!(fields(&act), ["3", "2", "310, 510"]);
+			// 210起動のジョブはデッドライン310に対して550で完了
+			assert_eq!(fields(&result)[1..], ["3", "3", "240 (delayed!)"]);
+		}
+	}
+
+	/**
+	優先度上限プロトコルではリソース占有中のTASKは優先度上限で実行され、
+	上限以下のTASKは占有解除まで待たされてブロッキング時間として計上されること
+	*/
+	#[test]
+	fn priority_ceiling_blocks_until_release() {
+		let lines = ["lo TASK WAITING 1 enable 300 50 offset=10 resource=r:0:30", "mid TASK WAITING 2 enable 300 20 offset=20", "hi TASK WAITING 3 enable 300 10 offset=25 resource=r:0:5"];
+		for tick_step in [false, true] {
+			let (log, blocking) = run_lines(&lines, TraceConfig::default(), 300, tick_step, |tracer| {
+				tracer.procs.iter().map(|proc| fields(&proc.get_blocking_result())).collect::<Vec<_>>()
+			});
+			// loは占有区間中は上限(hiの優先度)で実行し、midにもpreemptされない
+			assert_eq!(spans(&log, "lo", "RUNNING"), [(11, 41), (71, 90)]);
+			assert_eq!(spans(&log, "hi", "RUNNING"), [(41, 50)]);
+			assert_eq!(spans(&log, "mid", "RUNNING"), [(51, 70)]);
+			assert_eq!(blocking[1], ["20", "20", "0", "0"]);
+			assert_eq!(blocking[2], ["15", "15", "0", "0"]);
+		}
+		// リソースを使用しないTASKに対しては優先度上限が上がらない
+		let lines = ["lo TASK WAITING 1 enable 300 50 offset=10 resource=r:0:30", "mid TASK WAITING 2 enable 300 20 offset=20"];
+		let (log, _) = run_lines(&lines, TraceConfig::default(), 300, false, |_| ());
+		assert_eq!(spans(&log, "mid", "RUNNING"), [(21, 40)]);
+	}
+
+	/**
+	優先度継承ではミューテックス待ちのTASKはBLOCKEDとなり、占有中のTASKが待ちTASKの優先度を継承して
+	中間優先度のTASKにpreemptされず、優先度逆転区間は状態とは別の記録として通知されること
+	*/
+	#[test]
+	fn priority_inheritance_reports_inversion() {
+		let lines = ["lo TASK WAITING 1 enable 300 50 offset=10 mutex=m:0:30", "mid TASK WAITING 2 enable 300 20 offset=30", "hi TASK WAITING 3 enable 300 10 offset=25 mutex=m:0:5"];
+		for tick_step in [false, true] {
+			let (log, blocking) = run_lines(&lines, TraceConfig::default(), 300, tick_step, |tracer| fields(&proc_of(tracer, "hi").get_blocking_result()));
+			assert_eq!(spans(&log, "hi", "BLOCKED"), [(26, 40)]);
+			assert_eq!(spans(&log, "hi", "INVERSION"), [(26, 40)]);
+			assert_eq!(spans(&log, "mid", "RUNNING"), [(51, 70)]);
+			assert_eq!(blocking, ["14", "14", "1", "14"]);
+		}
+	}
+
+	/**
+	マルチコア(パーティション)では割り当てコア毎にスケジューリングし、
+	別コアのプロセスは並行に実行されること
+	*/
+	#[test]
+	fn partitioned_cores_run_in_parallel() {
+		let lines = ["a TASK WAITING 1 enable 100 40 offset=10", "b TASK WAITING 2 enable 100 30 offset=10 core=1", "c TASK WAITING 3 enable 100 20 offset=20"];
+		for tick_step in [false, true] {
+			let config = TraceConfig { core_num: 2, ..Default::default() };
+			let (log, busy) = run_lines(&lines, config, 100, tick_step, |tracer| tracer.cpu_use_busy.clone());
+			// cはコア0のaのみpreemptし、コア1のbは継続する
+			assert_eq!(spans(&log, "a", "RUNNING"), [(11, 21), (41, 70)]);
+			assert_eq!(spans(&log, "b", "RUNNING"), [(11, 40)]);
+			assert_eq!(spans(&log, "c", "RUNNING"), [(21, 40)]);
+			assert_eq!(busy, [60, 30]);
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid core: 2 (CoreNum=2) in b")]
+	fn partitioned_core_must_exist() {
+		let config = TraceConfig { core_num: 2, ..Default::default() };
+		run_lines(&["b TASK WAITING 2 enable 100 30 core=2"], config, 100, false, |_| ());
+	}
+
+	/**
+	グローバルスケジューリングではpreemptされたプロセスが空いた別コアで再開し、
+	マイグレーション回数とマイグレーションコストが計上されること
+	*/
+	#[test]
+	fn global_scheduling_migrates_to_free_core() {
+		let lines = ["a TASK WAITING 1 enable 200 60 offset=10", "b TASK WAITING 2 enable 200 30 offset=10 core=1", "c TASK WAITING 3 enable 200 40 offset=20"];
+		for (migration_cost, resumed) in [(0, (41, 90)), (5, (41, 95))] {
+			for tick_step in [false, true] {
+				let config = TraceConfig { core_num: 2, core_assign: CoreAssign::GLOBAL, migration_cost, ..Default::default() };
+				let (log, migration) = run_lines(&lines, config, 200, tick_step, |tracer| fields(&proc_of(tracer, "a").get_migration_result()));
+				// aはコア0でcにpreemptされ、bの完了したコア1で再開する
+				assert_eq!(spans(&log, "a", "RUNNING"), [(11, 21), resumed]);
+				assert_eq!(migration, ["1"]);
+			}
+		}
+		// パーティションではコア0のcの完了を待つ
+		let config = TraceConfig { core_num: 2, ..Default::default() };
+		let (log, _) = run_lines(&lines, config, 200, false, |_| ());
+		assert_eq!(spans(&log, "a", "RUNNING"), [(11, 21), (61, 110)]);
+	}
+
+	/**
+	カーネルオーバーヘッド指定時はTASKディスパッチ毎にスケジューラ起動+タスク切り替え、
+	INTRの開始/完了時にISR入口/出口の時間をkernel擬似プロセスとして計上すること
+	*/
+	#[test]
+	fn kernel_overhead_is_accounted() {
+		let lines = ["isr INTR WAITING 10 disable 200 10 offset=30", "t TASK WAITING 1 enable 200 40 offset=10"];
+		for tick_step in [false, true] {
+			let config = TraceConfig { overhead_task_switch: 2, overhead_scheduler: 1, overhead_isr_entry: 3, overhead_isr_exit: 4, ..Default::default() };
+			let (log, count) = run_lines(&lines, config, 200, tick_step, |tracer| fields(&tracer.kernel.as_ref().unwrap().get_count_result()));
+			// tのディスパッチ(3), isr入口(3), isr出口(4)+tの再ディスパッチ(3)
+			assert_eq!(spans(&log, "kernel", "RUNNING"), [(11, 14), (31, 34), (44, 51)]);
+			assert_eq!(spans(&log, "isr", "RUNNING"), [(34, 43)]);
+			assert_eq!(spans(&log, "t", "RUNNING"), [(14, 31), (51, 73)]);
+			assert_eq!(count, ["2", "1", "13"]);
+		}
+	}
+
+	/**
+	割り込み禁止区間中はマスクレベル以下のINTRは待たされ、より高いレベルのINTRのみ割り込めること
+	待たされた時間は割り込みレイテンシ/マスク待ちとして計上されること
+	*/
+	#[test]
+	fn intlock_masks_lower_levels() {
+		let lines = ["hi INTR WAITING 20 disable 300 5 level=2 offset=25", "lo INTR WAITING 10 disable 300 5 level=1 offset=20", "t TASK WAITING 1 enable 300 40 offset=10 intlock=5:20:1"];
+		for tick_step in [false, true] {
+			let (log, latency) = run_lines(&lines, TraceConfig::default(), 300, tick_step, |tracer| {
+				tracer.procs.iter().filter_map(|proc| proc.get_latency_result()).map(|result| fields(&result)).collect::<Vec<_>>()
+			});
+			assert_eq!(spans(&log, "hi", "RUNNING"), [(26, 30)]);
+			// loはtが禁止区間(ジョブ内処理時間25)を抜けるまで待たされる
+			assert_eq!(spans(&log, "lo", "RUNNING"), [(41, 45)]);
+			assert_eq!(latency, [["2", "1", "0", "0"], ["1", "21", "20", "20"]]);
+		}
+	}
+
+	/**
+	プリエンプション閾値指定時はディスパッチ後のTASKは閾値より高い優先度のTASKにのみpreemptされ、
+	閾値未指定のTASKはTaskUsePreemptionに従った既定値となること
+	*/
+	#[test]
+	fn preemption_threshold_limits_preemption() {
+		let lines = ["lo TASK WAITING 1 enable 300 40 offset=10 threshold=3", "mid TASK WAITING 2 enable 300 10 offset=20", "hi TASK WAITING 4 enable 300 10 offset=30"];
+		for tick_step in [false, true] {
+			let (log, _) = run_lines(&lines, TraceConfig::default(), 300, tick_step, |_| ());
+			// midは閾値3以下のため待たされ、hiのみpreemptする
+			assert_eq!(spans(&log, "lo", "RUNNING"), [(11, 31), (41, 60)]);
+			assert_eq!(spans(&log, "hi", "RUNNING"), [(31, 40)]);
+			assert_eq!(spans(&log, "mid", "RUNNING"), [(61, 70)]);
+		}
+		// 閾値未指定のloはnon-preemptiveなら閾値最大、preemptiveなら閾値=優先度
+		let lines = ["lo TASK WAITING 1 enable 300 40 offset=10", "mid TASK WAITING 2 enable 300 10 offset=20 threshold=2", "hi TASK WAITING 4 enable 300 10 offset=30"];
+		let (log, _) = run_lines(&lines, TraceConfig { task_use_preempt: false, ..Default::default() }, 300, false, |_| ());
+		assert_eq!(spans(&log, "lo", "RUNNING"), [(11, 50)]);
+		let (log, _) = run_lines(&lines, TraceConfig::default(), 300, false, |_| ());
+		assert_eq!(spans(&log, "lo", "RUNNING"), [(11, 21), (41, 70)]);
+		assert_eq!(spans(&log, "hi", "RUNNING"), [(31, 40)]);
+	}
+
+	/**
+	non-preemptiveでもスケジュールポイントで優先度の高いREADYのTASKに切り替わること
+	*/
+	#[test]
+	fn schedule_point_yields_to_higher_priority() {
+		let hi = "hi TASK WAITING 2 enable 300 10 offset=20";
+		for tick_step in [false, true] {
+			let config = TraceConfig { task_use_preempt: false, ..Default::default() };
+			let (log, _) = run_lines(&["lo TASK WAITING 1 enable 300 40 offset=10 sched=15,30", hi], config, 300, tick_step, |_| ());
+			// ジョブ内処理時間15で切り替え、30ではREADYのTASKが無いため継続
+			assert_eq!(spans(&log, "lo", "RUNNING"), [(11, 26), (36, 60)]);
+			assert_eq!(spans(&log, "hi", "RUNNING"), [(26, 35)]);
+		}
+		let config = TraceConfig { task_use_preempt: false, ..Default::default() };
+		let (log, _) = run_lines(&["lo TASK WAITING 1 enable 300 40 offset=10", hi], config, 300, false, |_| ());
+		assert_eq!(spans(&log, "lo", "RUNNING"), [(11, 50)]);
+	}
+
+	/**
+	起動トリガ指定のプロセスは起動元のジョブ完了時(OFFSET指定時はジョブ内処理時間到達時)に起動され、
+	チェーンの先頭起動から末尾完了までをEnd-to-Endレイテンシとして集計すること
+	*/
+	#[test]
+	fn trigger_chain_measures_end_to_end_latency() {
+		let lines = ["a TASK WAITING 1 enable 100 10 offset=10", "b TASK WAITING 2 enable 100 20 trigger=a", "c TASK WAITING 3 enable 100 5 trigger=b:10"];
+		for tick_step in [false, true] {
+			let config = TraceConfig { chains: vec![("ch".to_string(), vec!["a".to_string(), "b".to_string(), "c".to_string()])], ..Default::default() };
+			let (log, chain) = run_lines(&lines, config, 250, tick_step, |tracer| fields(&tracer.chains[0].get_result()));
+			// bはaの完了で起動し、cはbのジョブ内処理時間10で起動してbをpreemptする
+			assert_eq!(spans(&log, "b", "RUNNING")[..2], [(21, 31), (36, 45)]);
+			assert_eq!(spans(&log, "c", "RUNNING"), [(31, 35), (131, 135), (231, 235)]);
+			// aの起動10からcの完了35まで
+			assert_eq!(chain, ["3", "25", "25.00", "25"]);
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "invalid ChainInfo: c is not triggered by a in ch")]
+	fn trigger_chain_must_follow_triggers() {
+		let lines = ["a TASK WAITING 1 enable 100 10 offset=10", "b TASK WAITING 2 enable 100 20 trigger=a", "c TASK WAITING 3 enable 100 5 trigger=b:10"];
+		let config = TraceConfig { chains: vec![("ch".to_string(), vec!["a".to_string(), "c".to_string()])], ..Default::default() };
+		run_lines(&lines, config, 250, false, |_| ());
+	}
+
+	/**
+	イベントフラグのセットで待機中のプロセスが全て起床し、
+	メッセージキューは受信前に送信されたデータを待たずに受信できること
+	*/
+	#[test]
+	fn event_flag_wakes_all_and_queue_keeps_data() {
+		let lines = ["w1 TASK WAITING 2 enable 200 20 offset=10 waitflag=f:5", "w2 TASK WAITING 3 enable 200 20 offset=10 waitflag=f:5", "s TASK WAITING 1 enable 200 30 offset=10 setflag=f:10"];
+		for tick_step in [false, true] {
+			let (log, evwait) = run_lines(&lines, TraceConfig::default(), 200, tick_step, |tracer| {
+				tracer.procs.iter().map(|proc| fields(&proc.get_evwait_result())).collect::<Vec<_>>()
+			});
+			// sのジョブ内処理時間10(時刻30)で両方起床
+			assert_eq!(spans(&log, "w2", "EVWAIT"), [(15, 30)]);
+			assert_eq!(spans(&log, "w1", "EVWAIT"), [(20, 30)]);
+			assert_eq!(evwait, [["1", "10", "10"], ["1", "15", "15"], ["0", "0", "0"]]);
+		}
+		let lines = ["p TASK WAITING 2 enable 200 20 offset=10 send=q:5,q:10", "c TASK WAITING 1 enable 200 20 offset=10 recv=q:5,q:10"];
+		let (log, evwait) = run_lines(&lines, TraceConfig::default(), 200, false, |tracer| fields(&proc_of(tracer, "c").get_evwait_result()));
+		assert_eq!(spans(&log, "c", "RUNNING"), [(31, 50)]);
+		assert_eq!(evwait, ["0", "0", "0"]);
+	}
+
+	/**
+	アラーム/スケジュールテーブルの満了点でプロセスが起動され、起動先は起動周期では起動しないこと
+	*/
+	#[test]
+	fn alarms_and_schedule_tables_activate_processes() {
+		let lines = ["a TASK WAITING 1 enable 100 10", "b TASK WAITING 2 enable 200 10", "c TASK WAITING 3 enable 200 10"];
+		let releases = |log: &Log, name: &str| spans(log, name, "READY").iter().map(|span| span.0).collect::<Vec<i32>>();
+		for tick_step in [false, true] {
+			let alarms = vec![
+				ActivationTable::new("al".to_string(), AlarmKind::ALARM, 30, 100, vec![(0, vec!["a".to_string()])]),
+				ActivationTable::new("st".to_string(), AlarmKind::SCHEDTABLE, 0, 200, vec![(20, vec!["b".to_string()]), (120, vec!["b".to_string(), "c".to_string()])]),
+			];
+			let (log, results) = run_lines(&lines, TraceConfig { alarms, ..Default::default() }, 400, tick_step, |tracer| {
+				tracer.alarms.iter().map(|alarm| fields(&alarm.get_result())).collect::<Vec<_>>()
+			});
+			assert_eq!(releases(&log, "a"), [30, 130, 230, 330]);
+			assert_eq!(releases(&log, "b"), [20, 120, 220, 320]);
+			assert_eq!(releases(&log, "c"), [120, 320]);
+			assert_eq!(results, [["ALARM", "4", "4"], ["SCHEDTABLE", "4", "6"]]);
+		}
+		// 周期0のアラームは単発
+		let alarms = vec![ActivationTable::new("al".to_string(), AlarmKind::ALARM, 30, 0, vec![(0, vec!["a".to_string()])])];
+		let (log, _) = run_lines(&lines[..1], TraceConfig { alarms, ..Default::default() }, 400, false, |_| ());
+		assert_eq!(releases(&log, "a"), [30]);
+	}
+
+	/**
+	休止中のタスクへのイベントセットは無視され、待ち前にセットされたイベントは待たずに受け取り、ジョブ完了でクリアされること
+	*/
+	#[test]
+	fn extended_task_events_are_per_job() {
+		for tick_step in [false, true] {
+			// eの起動前(時刻10)のセットは無視され、時刻60から計測終了までイベント待ち
+			let lines = ["s TASK WAITING 10 enable 200 10 offset=5 setevent=e.ev:5", "e TASK WAITING 5 enable 200 20 offset=50 waitevent=ev:10"];
+			let (_, evwait) = run_lines(&lines, TraceConfig::default(), 190, tick_step, |tracer| fields(&proc_of(tracer, "e").get_evwait_result()));
+			assert_eq!(evwait, ["1", "129", "129"]);
+			// 1回目のジョブは待ち前にセット済みで待たず、2回目のジョブは完了でクリアされたため時刻120から待つ
+			let lines = ["s TASK WAITING 10 enable 200 10 offset=15 setevent=e.ev:5", "e TASK WAITING 5 enable 100 20 offset=10 waitevent=ev:10"];
+			let (log, evwait) = run_lines(&lines, TraceConfig::default(), 190, tick_step, |tracer| fields(&proc_of(tracer, "e").get_evwait_result()));
+			assert_eq!(spans(&log, "e", "RUNNING"), [(11, 16), (26, 40), (111, 120)]);
+			assert_eq!(evwait, ["1", "69", "69"]);
+		}
+	}
+
+	/**
+	処理時間の分布は[bcet,WCET]に収まり、同じシードでは同じ結果になること
+	*/
+	#[test]
+	fn exec_time_distribution_stays_in_range() {
+		let cases = [
+			("a TASK WAITING 1 enable 100 40 exec_mode=uniform bcet=20", 20, 40),
+			("a TASK WAITING 1 enable 100 40 exec_mode=normal bcet=20", 20, 40),
+			("a TASK WAITING 1 enable 100 40 exec_mode=hist bcet=20 exec_values=30:1,40:1", 20, 40),
+			("a TASK WAITING 1 enable 100 40 exec_mode=choice exec_values=10:1,30:3", 10, 30),
+		];
+		let exec = |line: &str, seed: u64, tick_step: bool| {
+			let (_, result) = run_lines(&[line], TraceConfig { seed, ..Default::default() }, 5000, tick_step, |tracer| fields(&proc_of(tracer, "a").get_exec_result().unwrap()));
+			result
+		};
+		for (line, bcet, wcet) in cases {
+			let seed1 = exec(line, 1, false);
+			assert_eq!(seed1[1], "49", "{}", line);
+			let min: i32 = seed1[2].parse().unwrap();
+			let max: i32 = seed1[4].parse().unwrap();
+			assert!(bcet <= min && min < max && max <= wcet, "{}: {:?}", line, seed1);
+			assert_eq!(exec(line, 1, true), seed1, "{}", line);
+			assert_ne!(exec(line, 2, false), seed1, "{}", line);
+		}
+	}
+
+	/**
+	モンテカルロ試行は試行毎に異なるシードでトレースし、パーセンタイルはnearest-rank法で集計すること
+	*/
+	#[test]
+	fn monte_carlo_runs_use_derived_seeds() {
+		let line = "a TASK WAITING 1 enable 100 10 offset=20 jitter=30 jitter_mode=random";
+		let releases = |seed: u64| {
+			let (log, _) = run_lines(&[line], TraceConfig { seed, ..Default::default() }, 500, false, |_| ());
+			spans(&log, "a", "READY").iter().map(|span| span.0).collect::<Vec<i32>>()
+		};
+		let runs: Vec<Vec<i32>> = (0..3).map(|run| releases(MonteCarloStats::run_seed(0, run))).collect();
+		assert_ne!(runs[0], runs[1]);
+		assert_ne!(runs[1], runs[2]);
+		assert_eq!(releases(MonteCarloStats::run_seed(0, 1)), runs[1]);
+		// 1～10の10試行: p50=5, p90=9, p99/p99.9=10
+		let values: Vec<f64> = (1..=10).map(|value| value as f64).collect();
+		assert_eq!(fields(&MonteCarloStats::get_load_result("a", &values)), ["5.50%", "5.00%", "9.00%", "10.00%", "10.00%", "10.00%"]);
+		let counts = [0.0, 0.0, 0.0, 2.0];
+		assert_eq!(fields(&MonteCarloStats::get_count_result("a", &counts)), ["1", "0.50", "0", "2", "2", "2", "2"]);
+	}
+
+	/**
+	モード切り替えで起動周期/処理時間/起動有無が切り替わり、モード毎に集計されること
+	*/
+	#[test]
+	fn mode_change_switches_parameters() {
+		let lines = ["a TASK WAITING 1 enable 100 10 mode.fast.cycle=50 mode.fast.time=20", "b TASK WAITING 2 enable 100 10 mode.fast.active=false"];
+		for tick_step in [false, true] {
+			let config = TraceConfig { mode_changes: vec![("fast".to_string(), 200), ("default".to_string(), 400)], ..Default::default() };
+			let (log, modes) = run_lines(&lines, config, 600, tick_step, |tracer| {
+				tracer.modes.iter().map(|mode| [fields(&mode.get_result(1)), fields(&mode.get_proc_result(0, "a")), fields(&mode.get_proc_result(1, "b"))].concat()).collect::<Vec<_>>()
+			});
+			// fastでは切り替え時点(200)から50周期で処理時間20, defaultに戻ると400から100周期
+			assert_eq!(spans(&log, "a", "RUNNING"), [(111, 120), (211, 220), (251, 270), (301, 320), (351, 370), (401, 420), (511, 520)]);
+			// bはfastの間は周期起動しない
+			assert_eq!(spans(&log, "b", "RUNNING"), [(101, 110), (201, 210), (501, 510)]);
+			assert_eq!(modes, [
+				["2", "399", "15.04%", "20.00%", "0", "10.00%", "0"],
+				["1", "200", "40.00%", "40.00%", "0", "10.00%", "0"],
+			]);
+		}
+	}
+
+	/**
+	測定値ファイルの指定列をファイルの順に使用し、処理時間欄の省略時は測定値の最大を処理時間欄とすること
+	*/
+	#[test]
+	fn time_file_replays_measured_values() {
+		let file = TempFile::new("cpu_usage_test_time_file.csv", "# measured\nid,time\n1,30\n2,12.4\nskip,-\n3,45\n");
+		let line = format!("a TASK WAITING 1 enable 100 time_file={}:time", file.0.display());
+		let mut time = vec![];
+		Settings::new().load_process(&line, &mut |_kind, _name, _state, _pri, _enable, _cycle, time_proc, _option| {
+			time = time_proc;
+		});
+		assert_eq!(time, [45]);
+		for tick_step in [false, true] {
+			let (log, exec) = run_lines(&[line.as_str()], TraceConfig::default(), 500, tick_step, |tracer| fields(&proc_of(tracer, "a").get_exec_result().unwrap()));
+			// 30, 12, 45の順で末尾の後は先頭に戻る
+			assert_eq!(spans(&log, "a", "RUNNING"), [(101, 130), (201, 212), (301, 345), (401, 430)]);
+			assert_eq!(exec, ["replay", "4", "12", "29.25", "45"]);
+		}
+	}
+
+	/**
+	処理時間は基準周波数とCPU周波数の比で換算され、周波数切り替え時は実行中のジョブの残り処理時間を換算すること
+	*/
+	#[test]
+	fn frequency_scales_exec_time() {
+		// CpuFreq=200, RefFreq=100で時刻210に100MHzへ切り替え, 2回目のジョブは200から実行して210で10進んだ状態
+		let cases = [
+			("a TASK WAITING 1 enable 100 40", [(101, 120), (201, 230), (301, 340)]),
+			("a TASK WAITING 1 enable 100 0 cycles=3000", [(101, 115), (201, 220), (301, 330)]),
+			("a TASK WAITING 1 enable 100 40 ref_freq=200", [(101, 140), (201, 270), (301, 380)]),
+		];
+		for (line, expected) in cases {
+			for tick_step in [false, true] {
+				let config = TraceConfig { cpu_freq: 200, ref_freq: 100, freq_changes: vec![(100, 210)], ..Default::default() };
+				let (log, _) = run_lines(&[line], config, 400, tick_step, |_| ());
+				assert_eq!(spans(&log, "a", "RUNNING"), expected, "{}", line);
+			}
 		}
-		println!("-{0:-<40}--+-{0:-<27}-", "");
-		println!("[{:40}] | {:10.2}% {:15} {}", "all", self.cpu_use_rate, "", delay);
 	}
 }
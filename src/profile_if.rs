@@ -1,16 +1,22 @@
-use std::io::Write;
-
 use crate::process::process::Process;
 use crate::process::process::ProcessKind;
+use crate::process::process_callback::ProcessCallback;
+use crate::process::process_callback::ProcessLog;
+use crate::process::process_montecarlo::MonteCarloStats;
+use crate::process::process_option::ProcessOption;
 use crate::process::process_state::ProcessState;
 use crate::process::process_tracer::ProcessTracer;
+use crate::process::process_tracer::TraceConfig;
 use crate::profiler::profiler::PlantUML;
 use crate::settings;
 
+/// 設定ファイルから読み出したプロセス定義
+type ProcessDef = (ProcessKind, String, ProcessState, i32, bool, i32, Vec<i32>, ProcessOption);
 
 pub struct ProfileIF
 {
 	input_file_path: String,
+	#[allow(dead_code)]
 	output_file_path: String,
 }
 
@@ -27,14 +33,15 @@ impl ProfileIF
 	pub fn run(&mut self, inp_base: String) {
 		let ( tx, rx) = std::sync::mpsc::channel();
 
-		let tx_clj = |name: &String, id: i32, state: ProcessState, begin: i32, end: i32, delayed: bool| {
+		let tx_clj = |name: &String, id: i32, log: ProcessLog, begin: i32, end: i32, delayed: bool| {
 			let tx = std::sync::mpsc::Sender::clone(&tx);
-			let fut = tx.send((name.clone(), id, state, begin, end, delayed));
+			let _ = tx.send((name.clone(), id, log, begin, end, delayed));
 		};
 		//let tx_clj = self.make_closure();
-		let mut procs_vec = vec![];
-		let mut init_clj = |kind: ProcessKind, name: String, state: ProcessState, pri: i32, enable: bool, cycle:i32, time: Vec<i32>| {
-			procs_vec.push(Process::new(kind, name, state, pri, enable, cycle, time, tx_clj));
+		// モンテカルロ試行ではプロセスを試行毎に作り直すため定義を保持する
+		let mut proc_defs: Vec<ProcessDef> = vec![];
+		let mut init_clj = |kind: ProcessKind, name: String, state: ProcessState, pri: i32, enable: bool, cycle:i32, time: Vec<i32>, option: ProcessOption| {
+			proc_defs.push((kind, name, state, pri, enable, cycle, time, option));
 		};
 		//let trace_time = self.load_process_info(&mut init_clj);
 		// ファイルから設定を読み出し
@@ -47,20 +54,27 @@ impl ProfileIF
 			}
 		}
 		let trace_time = *settings::TRACE_TIME.get().unwrap();
-		let task_use_preempt = *settings::TASK_USE_PREEMPT.get().unwrap();
+		// モンテカルロ試行
+		let monte_carlo = *settings::MONTE_CARLO.get().unwrap();
+		if monte_carlo > 1 {
+			ProfileIF::run_monte_carlo(&proc_defs, trace_time, monte_carlo);
+			ProfileIF::wait_enter();
+			return;
+		}
+		let procs_vec = ProfileIF::make_procs(&proc_defs, tx_clj);
+		let config = ProfileIF::make_config();
 		let pu_enable = *settings::PU_ENABLE.get().unwrap();
 		let pu_div_time = *settings::PU_DIVTIME.get().unwrap();
 
 		// トレース情報作成
-		let mut tracer = ProcessTracer::new(procs_vec, task_use_preempt);
-		let mut profiler_pu;
-		if pu_enable {
+		let mut tracer = ProcessTracer::new(procs_vec, config, tx_clj);
+		let profiler_pu = if pu_enable {
 			let mut pu = PlantUML::new(&inp_base, pu_div_time, trace_time);
-			pu.make_header(&tracer.procs);
-			profiler_pu = Some(pu);
+			pu.make_header(&tracer.procs, tracer.kernel.as_ref());
+			Some(pu)
 		} else {
-			profiler_pu = None;
-		}
+			None
+		};
 
 
 		let rx_clj = || {
@@ -96,7 +110,7 @@ impl ProfileIF
 		println!(">> trace start.");
 		tracer.run(trace_time);
 		println!(">> trace finish.");
-		println!("");
+		println!();
 		// 各プロセスの状況を出力
 		tracer.output_proc_result();
 		// トレース終了したらtxを破棄してワーカースレッド終了
@@ -105,11 +119,73 @@ impl ProfileIF
 		let join_result = rx_thread.join();
 		join_result.unwrap();
 
-		{
-			let mut buf = "".to_string();
-			println!("");
-			println!("Press Enter Key:");
-			std::io::stdin().read_line(&mut buf);
+		ProfileIF::wait_enter();
+	}
+
+	/**
+	モンテカルロ試行
+	試行毎にSeedと試行番号から導出したシードでトレースを繰り返し、結果の統計を出力する
+	PlantUMLは出力しない
+	*/
+	fn run_monte_carlo(proc_defs: &[ProcessDef], trace_time: i32, runs: i32) {
+		let log_clj = |_: &String, _: i32, _: ProcessLog, _: i32, _: i32, _: bool| {};
+		let names = proc_defs.iter().map(|def| def.1.clone()).collect();
+		let mut stats = MonteCarloStats::new(names);
+		println!(">> monte carlo start.");
+		for run in 0..runs {
+			let mut config = ProfileIF::make_config();
+			config.seed = MonteCarloStats::run_seed(config.seed, run);
+			let mut tracer = ProcessTracer::new(ProfileIF::make_procs(proc_defs, log_clj), config, log_clj);
+			tracer.run(trace_time);
+			stats.add(&tracer);
+			println!(">> run {}/{} finish.", run + 1, runs);
 		}
+		println!(">> monte carlo finish.");
+		println!();
+		stats.output();
+	}
+
+	/**
+	プロセス定義からプロセスを作成
+	*/
+	fn make_procs<T>(proc_defs: &[ProcessDef], cb: T) -> Vec<Process<T>>
+		where T: ProcessCallback + Copy
+	{
+		proc_defs.iter().cloned()
+			.map(|(kind, name, state, pri, enable, cycle, time, option)| Process::new(kind, name, state, pri, enable, cycle, time, option, cb))
+			.collect()
+	}
+
+	/**
+	設定ファイルからトレース設定を作成
+	*/
+	fn make_config() -> TraceConfig {
+		TraceConfig {
+			task_use_preempt: *settings::TASK_USE_PREEMPT.get().unwrap(),
+			intr_policy: *settings::INTR_SCHED_POLICY.get().unwrap(),
+			task_policy: *settings::TASK_SCHED_POLICY.get().unwrap(),
+			time_quantum: *settings::TIME_QUANTUM.get().unwrap(),
+			seed: *settings::SEED.get().unwrap(),
+			core_num: *settings::CORE_NUM.get().unwrap(),
+			core_assign: *settings::CORE_ASSIGN.get().unwrap(),
+			migration_cost: *settings::MIGRATION_COST.get().unwrap(),
+			overhead_task_switch: *settings::OVERHEAD_TASK_SWITCH.get().unwrap(),
+			overhead_isr_entry: *settings::OVERHEAD_ISR_ENTRY.get().unwrap(),
+			overhead_isr_exit: *settings::OVERHEAD_ISR_EXIT.get().unwrap(),
+			overhead_scheduler: *settings::OVERHEAD_SCHEDULER.get().unwrap(),
+			chains: settings::CHAIN_INFO.get().unwrap().clone(),
+			alarms: settings::ALARM_INFO.get().unwrap().clone(),
+			mode_changes: settings::MODE_CHANGE.get().unwrap().clone(),
+			cpu_freq: *settings::CPU_FREQ.get().unwrap(),
+			ref_freq: *settings::REF_FREQ.get().unwrap(),
+			freq_changes: settings::FREQ_CHANGE.get().unwrap().clone(),
+		}
+	}
+
+	fn wait_enter() {
+		let mut buf = "".to_string();
+		println!();
+		println!("Press Enter Key:");
+		let _ = std::io::stdin().read_line(&mut buf);
 	}
 }
\ No newline at end of file
@@ -4,8 +4,9 @@ use std::io::Write;
 use std::collections::{HashMap, LinkedList};
 
 use crate::process::process::Process;
-use crate::process::process_state::ProcessState;
 use crate::process::process_callback::ProcessCallback;
+use crate::process::process_callback::ProcessLog;
+use crate::process::process_kernel::ProcessKernel;
 
 
 type BuffContainer = LinkedList<String>;
@@ -27,6 +28,7 @@ pub struct PlantUML {
 	// 制御データ
 	last_time_proc: HashMap<i32,i32>,		// プロセス毎の最新CPU時間
 	last_time: i32,							// 前回CPU時間
+	proc_names: HashMap<i32,String>,		// プロセスID毎のプロセス名
 }
 
 impl PlantUML {
@@ -34,20 +36,18 @@ impl PlantUML {
 	pub fn new(_inp_base: &str, div_time: i32, trace_time: i32) -> PlantUML {
 		let mut div_enable = false;
 		let mut div_next = 0;
-		let mut div_max = 0;
 		let mut div_width: i32 = 1;
 		let div_count = 1;
-		let mut temp = 0;
 		if div_time > 0 {
 			div_enable = true;
 			div_next = div_time;
 			// 分割最大数計算
-			div_max = trace_time / div_time;
+			let mut div_max = trace_time / div_time;
 			if trace_time % div_time != 0 {
 				div_max += 1;
 			}
 			// 分割数の桁数
-			temp = div_max;
+			let mut temp = div_max;
 			while temp > 10 {
 				div_width += 1;
 				temp /= 10;
@@ -67,10 +67,11 @@ impl PlantUML {
 			footer: BuffContainer::new(),
 			last_time_proc: HashMap::new(),
 			last_time: -1,
+			proc_names: HashMap::new(),
 		}
 	}
 
-	pub fn make_header<T: ProcessCallback>(&mut self, _procs: &Vec<Process<T>>) {
+	pub fn make_header<T: ProcessCallback>(&mut self, _procs: &Vec<Process<T>>, _kernel: Option<&ProcessKernel<T>>) {
 		// 初期値設定
 		let mut init_value = BuffContainer::new();
 		// ヘッダ初期化
@@ -80,6 +81,14 @@ impl PlantUML {
 			self.header.push_back(format!("robust \"{}\" as W{}", _proc.name, _proc.id));
 			init_value.push_back(format!("W{} is WAITING", _proc.id));
 			self.last_time_proc.insert(_proc.id, -1);
+			self.proc_names.insert(_proc.id, _proc.name.clone());
+		}
+		// カーネル擬似プロセス
+		if let Some(_kernel) = _kernel {
+			self.header.push_back(format!("robust \"{}\" as W{}", _kernel.name, _kernel.id));
+			init_value.push_back(format!("W{} is WAITING", _kernel.id));
+			self.last_time_proc.insert(_kernel.id, -1);
+			self.proc_names.insert(_kernel.id, _kernel.name.clone());
 		}
 		self.header.push_back("".to_string());
 		self.header.append(&mut init_value);
@@ -87,12 +96,11 @@ impl PlantUML {
 
 	fn open_file(&mut self) -> Result<(),String> {
 		// ファイル名作成
-		let out_file: String;
-		if self.div_enable {
-			out_file = format!("{0}_{1:02$}.plantuml", self.out_file_base, self.div_count, self.div_width as usize);
+		let out_file = if self.div_enable {
+			format!("{0}_{1:02$}.plantuml", self.out_file_base, self.div_count, self.div_width as usize)
 		} else {
-			out_file = format!("{}.plantuml", self.out_file_base);
-		}
+			format!("{}.plantuml", self.out_file_base)
+		};
 		// ファイルオープンチェック
 		let out_path = std::path::Path::new(&out_file);
 		match std::fs::File::create(out_path) {
@@ -101,7 +109,7 @@ impl PlantUML {
 				Ok(())
 			}
 			Err(why) => {
-				return Err(format!("couldn't open {}: {}", out_path.display(), why.to_string()));
+				Err(format!("couldn't open {}: {}", out_path.display(), why))
 			}
 		}
 	}
@@ -117,14 +125,26 @@ impl PlantUML {
 		// ヘッダを先に出力
 		if let Some(writer) = self.output_fs.as_mut() {
 			for data in self.header.iter() {
-				writeln!(writer, "{}", data);
+				let _ = writeln!(writer, "{}", data);
 			}
 		}
 
 		Ok(())
 	}
 
-	pub fn profile(&mut self, name: &String, id: i32, state: ProcessState, log_cpu_time_begin: i32, log_cpu_time_end: i32, log_cycle_delayed: bool,) {
+	pub fn profile(&mut self, name: &String, id: i32, log: ProcessLog, log_cpu_time_begin: i32, log_cpu_time_end: i32, log_cycle_delayed: bool,) {
+		let state = match log {
+			ProcessLog::STATE(state) => state,
+			// 優先度逆転はハイライトのみ
+			ProcessLog::INVERSION(blocker) => {
+				let blocker_name = match self.proc_names.get(&blocker) {
+					Some(blocker_name) => blocker_name.as_str(),
+					None => "?",
+				};
+				self.footer.push_back(format!("highlight {} to {} #Pink;line:DimGrey : 優先度逆転({} <- {})", log_cpu_time_begin, log_cpu_time_end, name, blocker_name));
+				return;
+			},
+		};
 		// ログ時間チェック
 		// 時間補正:同じプロセス内で時間が重複したら+1して見た目上ずらす
 		let mut fixed_time = log_cpu_time_begin;
@@ -155,16 +175,14 @@ impl PlantUML {
 		self.output_body();
 
 		// ファイル分割チェック
-		if self.div_enable {
-			if log_cpu_time_end >= self.div_next {
-				// 分割情報更新
-				self.div_next += self.div_time;
-				self.div_count += 1;
-				// ファイルを閉じる
-				self.finish();
-				// ファイルを再度開く
-				self.start();
-			}
+		if self.div_enable && log_cpu_time_end >= self.div_next {
+			// 分割情報更新
+			self.div_next += self.div_time;
+			self.div_count += 1;
+			// ファイルを閉じる
+			self.finish();
+			// ファイルを再度開く
+			let _ = self.start();
 		}
 	}
 
@@ -178,7 +196,7 @@ impl PlantUML {
 		let mut buff = self.get_body();
 		if let Some(writer) = self.output_fs.as_mut() {
 			for data in buff.iter_mut() {
-				writeln!(writer, "{}", data);
+				let _ = writeln!(writer, "{}", data);
 			}
 		}
 	}
@@ -191,10 +209,10 @@ impl PlantUML {
 		// footer出力
 		if let Some(writer) = self.output_fs.as_mut() {
 			// 空行を挟む
-			writeln!(writer, "");
+			let _ = writeln!(writer);
 			for data in buff.iter_mut() {
 				//println!("{}", data);
-				writeln!(writer, "{}", data);
+				let _ = writeln!(writer, "{}", data);
 			}
 		}
 		// ファイルを閉じる
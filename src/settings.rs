@@ -1,10 +1,26 @@
 use std::io::BufRead;
 use regex::Regex;
 
-use crate::process::process::Process;
 use crate::process::process::ProcessKind;
 use crate::process::process_state::ProcessState;
-use crate::process::process_tracer::ProcessTracer;
+use crate::process::process_policy::SchedPolicy;
+use crate::process::process_policy::CoreAssign;
+use crate::process::process_option::ProcessOption;
+use crate::process::process_jitter::JitterMode;
+use crate::process::process_jitter::ReleaseJitter;
+use crate::process::process_arrival::ArrivalMode;
+use crate::process::process_arrival::SporadicArrival;
+use crate::process::process_exectime::ExecTimeMode;
+use crate::process::process_exectime::ExecTimeDist;
+use crate::process::process_resource::CriticalSection;
+use crate::process::process_resource::MutexSection;
+use crate::process::process_resource::IntLockSection;
+use crate::process::process_sync::SyncKind;
+use crate::process::process_sync::SyncOp;
+use crate::process::process_sync::SyncPoint;
+use crate::process::process_alarm::AlarmKind;
+use crate::process::process_alarm::ActivationTable;
+use crate::process::process_kernel::KERNEL_NAME;
 
 
 // 各種設定
@@ -13,6 +29,42 @@ use once_cell::sync::OnceCell;
 /// トレース時間
 pub static TRACE_TIME: OnceCell<i32> = OnceCell::new();
 pub static TASK_USE_PREEMPT: OnceCell<bool> = OnceCell::new();
+/// スケジューリングポリシー(INTR)
+pub static INTR_SCHED_POLICY: OnceCell<SchedPolicy> = OnceCell::new();
+/// スケジューリングポリシー(TASK)
+pub static TASK_SCHED_POLICY: OnceCell<SchedPolicy> = OnceCell::new();
+/// ラウンドロビンのタイムクォンタム
+pub static TIME_QUANTUM: OnceCell<i32> = OnceCell::new();
+/// 乱数シード
+pub static SEED: OnceCell<u64> = OnceCell::new();
+/// モンテカルロ試行回数
+pub static MONTE_CARLO: OnceCell<i32> = OnceCell::new();
+/// モード切り替えスケジュール(モード名, 切り替え時刻)
+pub static MODE_CHANGE: OnceCell<Vec<(String,i32)>> = OnceCell::new();
+/// CPU周波数(MHz), 0で周波数スケーリング無効
+pub static CPU_FREQ: OnceCell<i32> = OnceCell::new();
+/// 処理時間の基準周波数(MHz), 0でCPU周波数と同じ
+pub static REF_FREQ: OnceCell<i32> = OnceCell::new();
+/// 周波数切り替えスケジュール(周波数, 切り替え時刻)
+pub static FREQ_CHANGE: OnceCell<Vec<(i32,i32)>> = OnceCell::new();
+/// コア数
+pub static CORE_NUM: OnceCell<usize> = OnceCell::new();
+/// コア割り当て方式
+pub static CORE_ASSIGN: OnceCell<CoreAssign> = OnceCell::new();
+/// マイグレーションコスト
+pub static MIGRATION_COST: OnceCell<i32> = OnceCell::new();
+/// カーネルオーバーヘッド: タスク切り替え
+pub static OVERHEAD_TASK_SWITCH: OnceCell<i32> = OnceCell::new();
+/// カーネルオーバーヘッド: ISR入口
+pub static OVERHEAD_ISR_ENTRY: OnceCell<i32> = OnceCell::new();
+/// カーネルオーバーヘッド: ISR出口
+pub static OVERHEAD_ISR_EXIT: OnceCell<i32> = OnceCell::new();
+/// カーネルオーバーヘッド: スケジューラ起動
+pub static OVERHEAD_SCHEDULER: OnceCell<i32> = OnceCell::new();
+/// プロセスチェーン定義(チェーン名, 構成プロセス名)
+pub static CHAIN_INFO: OnceCell<Vec<(String,Vec<String>)>> = OnceCell::new();
+/// アラーム/スケジュールテーブル定義
+pub static ALARM_INFO: OnceCell<Vec<ActivationTable>> = OnceCell::new();
 // PlantUML
 pub static PU_ENABLE: OnceCell<bool> = OnceCell::new();
 /// 出力ファイル分割時間
@@ -27,89 +79,173 @@ enum LoadState {
 	PlantUML,
 	/// プロセス定義解析
 	ProcessInfo,
+	/// プロセスチェーン定義解析
+	ChainInfo,
+	/// カウンタ/アラーム/スケジュールテーブル定義解析
+	AlarmInfo,
 	None,
 }
 
 pub struct Settings
 {
+	/// 設定ファイルのディレクトリ(相対パス解決用)
+	base_dir: std::path::PathBuf,
 	// 正規表現定義
 	/// Regex: unsignedデータ解析
 	re_trace_info: Regex,
 	re_plant_uml: Regex,
 	/// Regex: プロセス定義解析
 	re_process: Regex,
+	/// Regex: プロセス定義の固定欄(名称～起動周期)解析
+	re_process_head: Regex,
 	re_time: Regex,
+	/// Regex: プロセス個別設定解析
+	re_option: Regex,
+	/// Regex: プロセスチェーン定義解析
+	re_chain: Regex,
+	/// Regex: カウンタ/アラーム/スケジュールテーブル定義解析
+	re_alarm: Regex,
 	// 設定ファイルから読みだしてOnceCellに渡すデータ
 	trace_time: i32,		// トレース時間
 	task_use_preempt: bool,		// 自動的にpreempt実施するかどうか
+	intr_sched_policy: SchedPolicy,		// スケジューリングポリシー(INTR)
+	task_sched_policy: SchedPolicy,		// スケジューリングポリシー(TASK)
+	time_quantum: i32,		// ラウンドロビンのタイムクォンタム
+	seed: u64,		// 乱数シード
+	monte_carlo: i32,		// モンテカルロ試行回数
+	mode_change: Vec<(String,i32)>,		// モード切り替えスケジュール
+	mode_names: std::collections::HashSet<String>,		// プロセス個別設定(mode.NAME.*)で使用したモード名
+	cpu_freq: i32,		// CPU周波数(MHz)
+	ref_freq: i32,		// 処理時間の基準周波数(MHz)
+	freq_change: Vec<(i32,i32)>,		// 周波数切り替えスケジュール
+	core_num: usize,		// コア数
+	core_assign: CoreAssign,		// コア割り当て方式
+	migration_cost: i32,		// マイグレーションコスト
+	overhead_task_switch: i32,		// カーネルオーバーヘッド: タスク切り替え
+	overhead_isr_entry: i32,		// カーネルオーバーヘッド: ISR入口
+	overhead_isr_exit: i32,		// カーネルオーバーヘッド: ISR出口
+	overhead_scheduler: i32,		// カーネルオーバーヘッド: スケジューラ起動
+	chain_info: Vec<(String,Vec<String>)>,		// プロセスチェーン定義
+	counters: std::collections::HashMap<String,i32>,		// カウンタ定義(カウンタ名, 1tick時間)
+	alarm_info: Vec<ActivationTable>,		// アラーム/スケジュールテーブル定義
 	pu_enable: bool,
 	pu_divtime: i32,
 }
 
+impl Default for Settings
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl Settings
 {
 
 	pub fn new() -> Settings {
 		// Settingsインスタンス作成
 		Settings{
-			re_trace_info: Regex::new(r"(\w+)\s*=\s*(\w+)").unwrap(),
+			base_dir: std::path::PathBuf::new(),
+			re_trace_info: Regex::new(r"(\w+)\s*=\s*([\w:,]+)").unwrap(),
 			re_plant_uml: Regex::new(r"(\w+)\s*=\s*(\w+)").unwrap(),
-			re_process: Regex::new(r"(\w+)\s+(\w+)\s+(\w+)\s+(\d+)\s+(\w+)\s+(\d+)((?:\s+(?:\d+))+)").unwrap(),
+			re_process: Regex::new(r"^\s*(\w+)\s+(\w+)\s+(\w+)\s+(\d+)\s+(\w+)\s+(\d+)((?:\s+(?:\d+))*)((?:\s+[\w.]+=\S+)*)\s*$").unwrap(),
+			re_process_head: Regex::new(r"^\s*(\w+)\s+(\w+)\s+(\w+)\s+(\d+)\s+(\w+)\s+(\d+)").unwrap(),
 			re_time: Regex::new(r"(\w+)").unwrap(),
+			re_option: Regex::new(r"([\w.]+)=(\S+)").unwrap(),
+			re_chain: Regex::new(r"(\w+)((?:\s+\w+)+)").unwrap(),
+			re_alarm: Regex::new(r"(\w+)\s+(\w+)((?:\s+[\w.]+=\S+)*)").unwrap(),
 			trace_time: 0,
 			task_use_preempt: true,
+			intr_sched_policy: SchedPolicy::FP,
+			task_sched_policy: SchedPolicy::FP,
+			time_quantum: 0,
+			seed: 0,
+			monte_carlo: 0,
+			mode_change: vec![],
+			mode_names: std::collections::HashSet::new(),
+			cpu_freq: 0,
+			ref_freq: 0,
+			freq_change: vec![],
+			core_num: 1,
+			core_assign: CoreAssign::PARTITIONED,
+			migration_cost: 0,
+			overhead_task_switch: 0,
+			overhead_isr_entry: 0,
+			overhead_isr_exit: 0,
+			overhead_scheduler: 0,
+			chain_info: vec![],
+			counters: std::collections::HashMap::new(),
+			alarm_info: vec![],
 			pu_enable: false,
 			pu_divtime: 0,
 		}
 	}
 
+	#[allow(clippy::single_match)]
 	pub fn load<T>(&mut self, input_file_path: &String, cb: &mut T) -> Result<(),String>
-		where T: FnMut(ProcessKind, String, ProcessState, i32, bool, i32, Vec<i32>) -> ()
+		where T: FnMut(ProcessKind, String, ProcessState, i32, bool, i32, Vec<i32>, ProcessOption)
 	{
 		// ファイルを開く
 		let inp_path = std::path::Path::new(&input_file_path);
+		if let Some(dir) = inp_path.parent() {
+			self.base_dir = dir.to_path_buf();
+		}
 		use std::fs::File;
 		//use std::error::Error;
 		use std::io::BufReader;
-		let file = match File::open(&inp_path) {
+		let file = match File::open(inp_path) {
 			Err(why) => {
-				return Err(format!("couldn't open {}: {}", inp_path.display(), why.to_string()));
+				return Err(format!("couldn't open {}: {}", inp_path.display(), why));
 			},
 			Ok(file) => file,
 		};
 		// ファイル読み込み
 		let mut state = LoadState::None;
-		for result in BufReader::new(file).lines() {
-			if result.is_ok() {
-				// 読み込んだテキストを解析
-				let line = result.unwrap();
-				if line.len() == 0 {
-					// 空行はスキップ
-				} else if (line.len() >= 2) && (&line[0..1] == "//") {
-					// コメントはスキップ
-				} else if &line[..1] == "[" {
-					// 先頭が[なら設定状態変更
-					state = self.check_load_state(&line);
-				} else {
-					// その他は設定値として解析
-					match state {
-						LoadState::TraceInfo => {
-							self.load_trace_info(&line);
-						},
-						LoadState::PlantUML => {
-							self.load_plant_uml(&line);
-						},
-						LoadState::ProcessInfo => {
-							self.load_process(&line, cb);
-						},
-						LoadState::None => {
-							// Noneは不明な状態なのでスキップ
-						}
+		for (idx, result) in BufReader::new(file).lines().enumerate() {
+			// 読み込めない行(不正なUTF-8等)は通知してスキップ
+			let line = match result {
+				Ok(line) => line,
+				Err(why) => {
+					println!("skip line {} in {}: {}", idx + 1, inp_path.display(), why);
+					continue;
+				},
+			};
+			// 読み込んだテキストを解析
+			if line.is_empty() {
+				// 空行はスキップ
+			} else if line.starts_with("//") {
+				// コメントはスキップ
+			} else if line.starts_with('[') {
+				// 先頭が[なら設定状態変更
+				state = self.check_load_state(&line);
+			} else {
+				// その他は設定値として解析
+				match state {
+					LoadState::TraceInfo => {
+						self.load_trace_info(&line);
+					},
+					LoadState::PlantUML => {
+						self.load_plant_uml(&line);
+					},
+					LoadState::ProcessInfo => {
+						self.load_process(&line, cb);
+					},
+					LoadState::ChainInfo => {
+						self.load_chain_info(&line);
+					},
+					LoadState::AlarmInfo => {
+						self.load_alarm_info(&line);
+					},
+					LoadState::None => {
+						// Noneは不明な状態なのでスキップ
 					}
 				}
 			}
 		}
 
+		// モード名の対応をチェック
+		self.check_mode_names();
+
 		// 読み込みが完了したらグローバル変数にセット
 		match TRACE_TIME.set(self.trace_time) {
 			Ok(_) => {}
@@ -119,6 +255,78 @@ impl Settings
 			Ok(_) => {}
 			Err(_) => {}
 		}
+		match INTR_SCHED_POLICY.set(self.intr_sched_policy) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match TASK_SCHED_POLICY.set(self.task_sched_policy) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match TIME_QUANTUM.set(self.time_quantum) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match SEED.set(self.seed) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match MONTE_CARLO.set(self.monte_carlo) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match MODE_CHANGE.set(std::mem::take(&mut self.mode_change)) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match CPU_FREQ.set(self.cpu_freq) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match REF_FREQ.set(self.ref_freq) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match FREQ_CHANGE.set(std::mem::take(&mut self.freq_change)) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match CORE_NUM.set(self.core_num) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match CORE_ASSIGN.set(self.core_assign) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match MIGRATION_COST.set(self.migration_cost) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match OVERHEAD_TASK_SWITCH.set(self.overhead_task_switch) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match OVERHEAD_ISR_ENTRY.set(self.overhead_isr_entry) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match OVERHEAD_ISR_EXIT.set(self.overhead_isr_exit) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match OVERHEAD_SCHEDULER.set(self.overhead_scheduler) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match CHAIN_INFO.set(std::mem::take(&mut self.chain_info)) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
+		match ALARM_INFO.set(std::mem::take(&mut self.alarm_info)) {
+			Ok(_) => {}
+			Err(_) => {}
+		}
 		match PU_ENABLE.set(self.pu_enable) {
 			Ok(_) => {}
 			Err(_) => {}
@@ -139,6 +347,8 @@ impl Settings
 				LoadState::PlantUML
 			},
 			"[ProcessInfo]"		=> LoadState::ProcessInfo,
+			"[ChainInfo]"		=> LoadState::ChainInfo,
+			"[AlarmInfo]"		=> LoadState::AlarmInfo,
 			_					=> panic!("undefined Setting: {}", _text),
 		}
 	}
@@ -170,6 +380,164 @@ impl Settings
 							}
 						}
 					}
+					"SchedPolicy" => {
+						// 全プロセスに適用
+						match Settings::load_sched_policy(val) {
+							Ok(policy) => {
+								self.intr_sched_policy = policy;
+								self.task_sched_policy = policy;
+							},
+							Err(_) => {
+								println!("invalid SchedPolicy: {}", val);
+							}
+						}
+					}
+					"IntrSchedPolicy" => {
+						match Settings::load_sched_policy(val) {
+							Ok(policy) => {
+								self.intr_sched_policy = policy;
+							},
+							Err(_) => {
+								println!("invalid IntrSchedPolicy: {}", val);
+							}
+						}
+					}
+					"TaskSchedPolicy" => {
+						match Settings::load_sched_policy(val) {
+							Ok(policy) => {
+								self.task_sched_policy = policy;
+							},
+							Err(_) => {
+								println!("invalid TaskSchedPolicy: {}", val);
+							}
+						}
+					}
+					"TimeQuantum" => {
+						match val.parse::<i32>() {
+							Ok(time) => {
+								self.time_quantum = time;
+							},
+							Err(_) => {
+								println!("invalid TimeQuantum: {}", val);
+							}
+						}
+					}
+					"Seed" => {
+						match val.parse::<u64>() {
+							Ok(seed) => {
+								self.seed = seed;
+							},
+							Err(_) => {
+								println!("invalid Seed: {}", val);
+							}
+						}
+					}
+					"CpuFreq" => {
+						match val.parse::<i32>() {
+							Ok(freq) if freq >= 0 => {
+								self.cpu_freq = freq;
+							},
+							_ => {
+								println!("invalid CpuFreq: {}", val);
+							}
+						}
+					}
+					"RefFreq" => {
+						match val.parse::<i32>() {
+							Ok(freq) if freq >= 0 => {
+								self.ref_freq = freq;
+							},
+							_ => {
+								println!("invalid RefFreq: {}", val);
+							}
+						}
+					}
+					"FreqChange" => {
+						self.freq_change = Settings::load_freq_change(val);
+					}
+					"ModeChange" => {
+						self.mode_change = Settings::load_mode_change(val);
+					}
+					"MonteCarlo" => {
+						match val.parse::<i32>() {
+							Ok(num) if num >= 0 => {
+								self.monte_carlo = num;
+							},
+							_ => {
+								println!("invalid MonteCarlo: {}", val);
+							}
+						}
+					}
+					"CoreNum" => {
+						match val.parse::<usize>() {
+							Ok(num) if num > 0 => {
+								self.core_num = num;
+							},
+							_ => {
+								println!("invalid CoreNum: {}", val);
+							}
+						}
+					}
+					"CoreAssign" => {
+						match Settings::load_core_assign(val) {
+							Ok(assign) => {
+								self.core_assign = assign;
+							},
+							Err(_) => {
+								println!("invalid CoreAssign: {}", val);
+							}
+						}
+					}
+					"MigrationCost" => {
+						match val.parse::<i32>() {
+							Ok(time) => {
+								self.migration_cost = time;
+							},
+							Err(_) => {
+								println!("invalid MigrationCost: {}", val);
+							}
+						}
+					}
+					"TaskSwitch" => {
+						match val.parse::<i32>() {
+							Ok(time) => {
+								self.overhead_task_switch = time;
+							},
+							Err(_) => {
+								println!("invalid TaskSwitch: {}", val);
+							}
+						}
+					}
+					"IsrEntry" => {
+						match val.parse::<i32>() {
+							Ok(time) => {
+								self.overhead_isr_entry = time;
+							},
+							Err(_) => {
+								println!("invalid IsrEntry: {}", val);
+							}
+						}
+					}
+					"IsrExit" => {
+						match val.parse::<i32>() {
+							Ok(time) => {
+								self.overhead_isr_exit = time;
+							},
+							Err(_) => {
+								println!("invalid IsrExit: {}", val);
+							}
+						}
+					}
+					"Scheduler" => {
+						match val.parse::<i32>() {
+							Ok(time) => {
+								self.overhead_scheduler = time;
+							},
+							Err(_) => {
+								println!("invalid Scheduler: {}", val);
+							}
+						}
+					}
 					_ => {
 						// 何もしない
 					}
@@ -182,7 +550,7 @@ impl Settings
 	}
 
 	fn load_plant_uml(&mut self, _text: &str) {
-		let capture_opt = self.re_plant_uml.captures(&_text);
+		let capture_opt = self.re_plant_uml.captures(_text);
 		match capture_opt {
 			Some(cap) => {
 				let key = &cap[1];
@@ -219,16 +587,180 @@ impl Settings
 		}
 	}
 
+	/**
+	プロセスチェーン定義解析
+	チェーン名に続けて構成プロセス名を起動順に指定する
+	*/
+	fn load_chain_info(&mut self, _text: &str) {
+		match self.re_chain.captures(_text) {
+			Some(caps) => {
+				let name = caps[1].to_string();
+				let procs: Vec<String> = caps[2].split_whitespace().map(|proc| proc.to_string()).collect();
+				if procs.len() < 2 {
+					panic!("invalid ChainInfo: {}", _text);
+				}
+				self.chain_info.push((name, procs));
+			},
+			None => {
+				// マッチしないものはスキップ
+			}
+		}
+	}
+
+	/**
+	モード切り替えスケジュール解析
+	MODE:TIME をカンマ区切りで複数指定可能, 時刻順に並べる
+	*/
+	fn load_mode_change(text: &str) -> Vec<(String,i32)> {
+		let mut changes = vec![];
+		for item in text.split(',') {
+			let fields: Vec<&str> = item.split(':').collect();
+			if fields.len() != 2 {
+				panic!("invalid ModeChange: {}", item);
+			}
+			changes.push((fields[0].to_string(), Settings::load_process_i32("ModeChange", fields[1])));
+		}
+		changes.sort_by_key(|(_, time)| *time);
+		changes
+	}
+
+	/**
+	モード名チェック
+	ModeChangeとプロセス個別設定(mode.NAME.*)のモード名は互いに対応していなければならない
+	defaultは基本設定に戻すモードとして個別設定無しで指定可能
+	*/
+	fn check_mode_names(&self) {
+		for name in self.mode_names.iter() {
+			if !self.mode_change.iter().any(|(mode, _)| mode == name) {
+				panic!("invalid mode: {} is not in ModeChange", name);
+			}
+		}
+		for (name, _) in self.mode_change.iter() {
+			if name != "default" && !self.mode_names.contains(name) {
+				panic!("invalid ModeChange: {} has no mode.{}.* in ProcessInfo", name, name);
+			}
+		}
+	}
+
+	/**
+	周波数切り替えスケジュール解析
+	FREQ:TIME をカンマ区切りで複数指定可能, 時刻順に並べる
+	*/
+	fn load_freq_change(text: &str) -> Vec<(i32,i32)> {
+		let mut changes = vec![];
+		for item in text.split(',') {
+			let fields: Vec<&str> = item.split(':').collect();
+			if fields.len() != 2 {
+				panic!("invalid FreqChange: {}", item);
+			}
+			changes.push((Settings::load_freq("FreqChange", fields[0]), Settings::load_process_i32("FreqChange", fields[1])));
+		}
+		changes.sort_by_key(|(_, time)| *time);
+		changes
+	}
+
+	/**
+	カウンタ/アラーム/スケジュールテーブル定義解析
+	COUNTER    名称 tick=N
+	ALARM      名称 counter=C start=N cycle=N activate=P1,P2,... setevent=TASK.EVENT,...
+	SCHEDTABLE 名称 counter=C start=N duration=N repeat=true/false expiry=OFFSET:P1+TASK.EVENT,...
+	時間はカウンタのtick数で指定する(counter省略時は1tick=1us)
+	TASK.EVENT形式の起動先はプロセス起動ではなくイベントのセットとする
+	*/
+	fn load_alarm_info(&mut self, _text: &str) {
+		let caps = match self.re_alarm.captures(_text) {
+			Some(caps) => caps,
+			None => {
+				// マッチしないものはスキップ
+				return;
+			}
+		};
+		let kind = caps[1].to_string();
+		let name = caps[2].to_string();
+		let mut tick: i32 = 1;
+		let mut start: i32 = 0;
+		let mut period: i32 = 0;
+		let mut repeat = false;
+		let mut activate: Vec<String> = vec![];
+		let mut expiry: Vec<(i32, Vec<String>)> = vec![];
+		for cap in self.re_option.captures_iter(&caps[3]) {
+			let key = &cap[1];
+			let val = &cap[2];
+			match key {
+				"tick" => {
+					tick = Settings::load_process_i32(key, val);
+				}
+				"counter" => {
+					tick = match self.counters.get(val) {
+						Some(tick) => *tick,
+						None => panic!("undefined counter: {}", val),
+					};
+				}
+				"start" => {
+					start = Settings::load_process_i32(key, val);
+				}
+				"cycle" | "duration" => {
+					period = Settings::load_process_i32(key, val);
+				}
+				"repeat" => {
+					repeat = match Settings::load_bool(val) {
+						Ok(repeat) => repeat,
+						Err(msg) => panic!("{}", msg),
+					};
+				}
+				"activate" | "setevent" => {
+					activate.extend(val.split(',').map(|proc| proc.to_string()));
+				}
+				"expiry" => {
+					for item in val.split(',') {
+						let fields: Vec<&str> = item.split(':').collect();
+						if fields.len() != 2 {
+							panic!("invalid {}: {}", key, item);
+						}
+						let offset = Settings::load_process_i32(key, fields[0]);
+						expiry.push((offset, fields[1].split('+').map(|proc| proc.to_string()).collect()));
+					}
+				}
+				_ => panic!("invalid AlarmInfo: {}", key),
+			}
+		}
+		match kind.as_str() {
+			"COUNTER" => {
+				self.counters.insert(name, tick);
+			},
+			"ALARM" => {
+				let table = ActivationTable::new(name, AlarmKind::ALARM, start * tick, period * tick, vec![(0, activate)]);
+				self.alarm_info.push(table);
+			},
+			"SCHEDTABLE" => {
+				if expiry.iter().any(|(offset, _)| repeat && *offset >= period) {
+					panic!("invalid SCHEDTABLE: expiry offset must be less than duration in {}", name);
+				}
+				if !repeat {
+					period = 0;
+				}
+				let expiry = expiry.into_iter().map(|(offset, procs)| (offset * tick, procs)).collect();
+				let table = ActivationTable::new(name, AlarmKind::SCHEDTABLE, start * tick, period * tick, expiry);
+				self.alarm_info.push(table);
+			},
+			_ => panic!("invalid AlarmInfo: {}", kind),
+		}
+	}
+
 	pub fn load_process<T>(&mut self, _text: &str, cb: &mut T)
-		where T: FnMut(ProcessKind, String, ProcessState, i32, bool, i32, Vec<i32>) -> ()
+		where T: FnMut(ProcessKind, String, ProcessState, i32, bool, i32, Vec<i32>, ProcessOption)
 	{
 		// ProcessInfo取得
 		// 正規表現でチェック
-		let capture = self.re_process.captures(&_text);
+		let capture = self.re_process.captures(_text);
 		match capture {
 			Some(caps) => {
 				// データ取得
 				let name = caps[1].to_string();
+				// カーネル擬似プロセスと同名は区別できないため不可
+				if name == KERNEL_NAME {
+					panic!("invalid ProcessInfo: {} is reserved for the kernel pseudo-process", name);
+				}
 				let kind = Settings::load_process_intr_task(&caps[2]);
 				let state = Settings::load_process_state(&caps[3]);
 				let pri: i32 = caps[4].parse::<i32>().unwrap();
@@ -240,15 +772,444 @@ impl Settings
 				for mat in self.re_time.find_iter(time) {
 					time_vec.push(mat.as_str().parse::<i32>().unwrap());
 				}
+				// 個別設定
+				let mut option = self.load_process_option(&name, &caps[8]);
+				self.mode_names.extend(option.modes.keys().cloned());
+				// サイクル数指定時は1MHzで1サイクル=1usとして処理時間欄の代わりに使用
+				if !option.cycles.is_empty() {
+					if option.ref_freq.is_some() {
+						panic!("invalid ProcessOption: cycles cannot be used with ref_freq in {}", name);
+					}
+					time_vec = std::mem::take(&mut option.cycles);
+					option.ref_freq = Some(1);
+				}
+				// 処理時間欄は測定値ファイル指定時のみ省略可能(測定値の最大を処理時間欄とする)
+				if time_vec.is_empty() {
+					match option.exec_time.as_ref() {
+						Some(dist) if dist.is_measured() => time_vec.push(dist.get_max_value()),
+						_ => panic!("invalid ProcessInfo: processing time is required in {}", name),
+					}
+				}
+				// 最短処理時間は処理時間欄の先頭(WCET)以下, モード別処理時間も同様
+				if let Some(dist) = option.exec_time.as_ref() {
+					let mode_times = option.modes.values().filter_map(|param| param.time_proc.as_ref());
+					for wcet in std::iter::once(&time_vec).chain(mode_times).filter_map(|times| times.first()) {
+						if dist.get_bcet() > *wcet {
+							panic!("invalid ProcessOption: bcet={} exceeds WCET {} in {}", dist.get_bcet(), wcet, name);
+						}
+					}
+				}
 				//procs_vec.push(Process::new(kind, name, pri, enable, cycle, [100].to_vec(), cb));
-				cb(kind, name, state, pri, enable, cycle, time_vec);
+				cb(kind, name, state, pri, enable, cycle, time_vec, option);
 			}
 			None => {
-				// 何もしない
+				// 固定欄は解析できるが、以降に処理時間/個別設定として解析できない欄があれば設定誤り
+				if let Some(caps) = self.re_process_head.captures(_text) {
+					panic!("invalid ProcessInfo: cannot parse '{}' in {}", _text[caps[0].len()..].trim(), &caps[1]);
+				}
+				// それ以外(旧形式等)は何もしない
 			}
 		}
 	}
 
+	fn load_process_option(&self, name: &str, text: &str) -> ProcessOption {
+		let mut option = ProcessOption::default();
+		let mut jitter: Vec<i32> = vec![];
+		let mut jitter_mode = JitterMode::MAX;
+		let mut sporadic: Option<ArrivalMode> = None;
+		let mut max_interval: i32 = 0;
+		let mut arrivals: Vec<i32> = vec![];
+		let mut exec_mode: Option<ExecTimeMode> = None;
+		let mut bcet: Option<i32> = None;
+		let mut mean: Option<f64> = None;
+		let mut sigma: Option<f64> = None;
+		let mut exec_values: Vec<(i32, i32)> = vec![];
+		let mut time_file: Option<String> = None;
+		let mut time_file_mode = ExecTimeMode::REPLAY;
+		for cap in self.re_option.captures_iter(text) {
+			let key = &cap[1];
+			let val = &cap[2];
+			match key {
+				"quantum" => {
+					option.time_quantum = Some(Settings::load_process_i32(key, val));
+				}
+				"deadline" => {
+					option.time_deadline = Some(Settings::load_process_i32(key, val));
+				}
+				"offset" => {
+					option.time_offset = Some(Settings::load_process_i32(key, val));
+				}
+				"jitter" => {
+					jitter = Settings::load_process_i32_list(key, val);
+				}
+				"jitter_mode" => {
+					jitter_mode = Settings::load_process_jitter_mode(val);
+				}
+				"sporadic" => {
+					sporadic = Some(Settings::load_process_arrival_mode(val));
+				}
+				"max_interval" => {
+					max_interval = Settings::load_process_i32(key, val);
+				}
+				"arrivals" => {
+					arrivals = Settings::load_process_i32_list(key, val);
+				}
+				"exec_mode" => {
+					exec_mode = Settings::load_process_exec_mode(val);
+				}
+				"bcet" => {
+					bcet = Some(Settings::load_process_i32(key, val));
+				}
+				"mean" => {
+					mean = Some(Settings::load_process_f64(key, val));
+				}
+				"sigma" => {
+					sigma = Some(Settings::load_process_f64(key, val));
+				}
+				"exec_values" => {
+					exec_values = Settings::load_process_exec_values(key, val);
+				}
+				"ref_freq" => {
+					option.ref_freq = Some(Settings::load_freq(key, val));
+				}
+				"cycles" => {
+					option.cycles = Settings::load_process_i32_list(key, val);
+				}
+				"time_file" => {
+					time_file = Some(val.to_string());
+				}
+				"time_file_mode" => {
+					time_file_mode = match val {
+						"replay" => ExecTimeMode::REPLAY,
+						"sample" => ExecTimeMode::SAMPLE,
+						_ => panic!("invalid time_file_mode: {}", val),
+					};
+				}
+				"act_limit" => {
+					option.act_limit = Some(Settings::load_process_i32(key, val));
+				}
+				"resource" => {
+					option.critical_sections = Settings::load_process_critical_sections(val);
+				}
+				"mutex" => {
+					option.mutex_sections = Settings::load_process_sections(key, val).into_iter()
+						.map(|(name, offset, length)| MutexSection::new(name, offset, length))
+						.collect();
+				}
+				"core" => {
+					match val.parse::<usize>() {
+						Ok(core) => {
+							option.core = Some(core);
+						},
+						Err(_) => panic!("invalid {}: {}", key, val),
+					}
+				}
+				"level" => {
+					option.level = Some(Settings::load_process_i32(key, val));
+				}
+				"intlock" => {
+					option.intlock_sections = Settings::load_process_intlock_sections(key, val);
+				}
+				"threshold" => {
+					option.threshold = Some(Settings::load_process_i32(key, val));
+				}
+				"sched" => {
+					option.sched_points = Settings::load_process_i32_list(key, val);
+				}
+				"trigger" => {
+					let fields: Vec<&str> = val.split(':').collect();
+					option.trigger = match fields.len() {
+						1 => Some((fields[0].to_string(), None)),
+						2 => Some((fields[0].to_string(), Some(Settings::load_process_i32(key, fields[1])))),
+						_ => panic!("invalid {}: {}", key, val),
+					};
+				}
+				"send" => {
+					option.sync_points.append(&mut Settings::load_process_sync_points(key, val, SyncKind::QUEUE, SyncOp::POST));
+				}
+				"recv" => {
+					option.sync_points.append(&mut Settings::load_process_sync_points(key, val, SyncKind::QUEUE, SyncOp::TAKE));
+				}
+				"setflag" => {
+					option.sync_points.append(&mut Settings::load_process_sync_points(key, val, SyncKind::FLAG, SyncOp::POST));
+				}
+				"waitflag" => {
+					option.sync_points.append(&mut Settings::load_process_sync_points(key, val, SyncKind::FLAG, SyncOp::TAKE));
+				}
+				"setevent" => {
+					for point in Settings::load_process_sync_points(key, val, SyncKind::EVENT, SyncOp::POST) {
+						if !point.object.contains('.') {
+							panic!("invalid {}: {} (TASK.EVENT)", key, point.object);
+						}
+						option.sync_points.push(point);
+					}
+				}
+				"waitevent" => {
+					// 待機できるのは自タスクのイベントのみ
+					for mut point in Settings::load_process_sync_points(key, val, SyncKind::EVENT, SyncOp::TAKE) {
+						point.object = format!("{}.{}", name, point.object);
+						option.sync_points.push(point);
+					}
+				}
+				_ if key.starts_with("mode.") => {
+					Settings::load_process_mode_param(&mut option, key, val);
+				}
+				_ => panic!("invalid ProcessOption: {}", key),
+			}
+		}
+		// ジッタが複数指定されていれば順番に適用
+		if jitter.len() > 1 {
+			jitter_mode = JitterMode::SEQUENCE;
+		}
+		if !jitter.is_empty() {
+			option.release_jitter = Some(ReleaseJitter::new(jitter_mode, jitter));
+		}
+		// 到着時刻リストが指定されていればLIST
+		if !arrivals.is_empty() && sporadic.is_none() {
+			sporadic = Some(ArrivalMode::LIST);
+		}
+		if let Some(mode) = sporadic {
+			option.sporadic = Some(SporadicArrival::new(mode, max_interval, arrivals));
+		}
+		if let Some(mode) = exec_mode {
+			// ヒストグラム/重み付き選択は正の度数/重みが必要
+			let use_values = matches!(mode, ExecTimeMode::HISTOGRAM | ExecTimeMode::CHOICE);
+			if use_values && (exec_values.iter().any(|(_, weight)| *weight < 0) || exec_values.iter().all(|(_, weight)| *weight == 0)) {
+				panic!("invalid exec_values: {} requires VALUE:WEIGHT,...", mode);
+			}
+			// 一様乱数/正規分布/ヒストグラムは最短処理時間が必要
+			let use_bcet = matches!(mode, ExecTimeMode::UNIFORM | ExecTimeMode::NORMAL | ExecTimeMode::HISTOGRAM);
+			if use_bcet && bcet.is_none() {
+				panic!("invalid ProcessOption: exec_mode={} requires bcet in {}", mode, name);
+			}
+			option.exec_time = Some(ExecTimeDist::new(mode, bcet.unwrap_or(0), mean, sigma, exec_values));
+		}
+		// 測定値ファイル
+		if let Some(path) = time_file.as_deref() {
+			if option.exec_time.is_some() {
+				panic!("invalid time_file: exec_mode cannot be used with time_file in {}", name);
+			}
+			// 処理時間は測定値ファイルから生成するためモード別の処理時間は使用できない
+			if option.modes.values().any(|param| param.time_proc.is_some()) {
+				panic!("invalid time_file: mode.NAME.time cannot be used with time_file in {}", name);
+			}
+			let values = self.load_process_time_file(path).into_iter().map(|value| (value, 1)).collect();
+			option.exec_time = Some(ExecTimeDist::new(time_file_mode, 0, None, None, values));
+		}
+		// 同期ポイントはジョブ内処理時間順に操作する
+		option.sync_points.sort_by_key(|point| point.offset);
+		option
+	}
+
+	/**
+	モード別パラメータ解析
+	mode.NAME.cycle=N, mode.NAME.time=T1,T2,..., mode.NAME.active=true/false
+	*/
+	fn load_process_mode_param(option: &mut ProcessOption, key: &str, val: &str) {
+		let fields: Vec<&str> = key.split('.').collect();
+		if fields.len() != 3 {
+			panic!("invalid ProcessOption: {}", key);
+		}
+		// defaultは基本設定に戻すモードのため個別設定不可
+		if fields[1] == "default" {
+			panic!("invalid ProcessOption: mode.default is reserved for the base settings");
+		}
+		let param = option.modes.entry(fields[1].to_string()).or_default();
+		match fields[2] {
+			"cycle" => {
+				param.cycle = Some(Settings::load_process_i32(key, val));
+			}
+			"time" => {
+				param.time_proc = Some(Settings::load_process_i32_list(key, val));
+			}
+			"active" => {
+				param.active = match Settings::load_bool(val) {
+					Ok(active) => Some(active),
+					Err(msg) => panic!("{}", msg),
+				};
+			}
+			_ => panic!("invalid ProcessOption: {}", key),
+		}
+	}
+
+	fn load_process_critical_sections(text: &str) -> Vec<CriticalSection> {
+		Settings::load_process_sections("resource", text).into_iter()
+			.map(|(name, offset, length)| CriticalSection::new(name, offset, length))
+			.collect()
+	}
+
+	/**
+	同期ポイント解析
+	NAME:OFFSET をカンマ区切りで複数指定可能
+	*/
+	fn load_process_sync_points(key: &str, text: &str, kind: SyncKind, op: SyncOp) -> Vec<SyncPoint> {
+		let mut points = vec![];
+		for item in text.split(',') {
+			let fields: Vec<&str> = item.split(':').collect();
+			if fields.len() != 2 {
+				panic!("invalid {}: {}", key, item);
+			}
+			let offset = Settings::load_process_i32(key, fields[1]);
+			points.push(SyncPoint::new(fields[0].to_string(), kind, op, offset));
+		}
+		points
+	}
+
+	/**
+	割り込み禁止区間解析
+	OFFSET:LENGTH[:LEVEL] をカンマ区切りで複数指定可能, LEVEL省略時は全割り込み禁止
+	*/
+	fn load_process_intlock_sections(key: &str, text: &str) -> Vec<IntLockSection> {
+		let mut sections = vec![];
+		for item in text.split(',') {
+			let fields: Vec<&str> = item.split(':').collect();
+			let level = match fields.len() {
+				2 => i32::MAX,
+				3 => Settings::load_process_i32(key, fields[2]),
+				_ => panic!("invalid {}: {}", key, item),
+			};
+			let offset = Settings::load_process_i32(key, fields[0]);
+			let length = Settings::load_process_i32(key, fields[1]);
+			sections.push(IntLockSection::new(offset, length, level));
+		}
+		sections
+	}
+
+	/**
+	占有区間解析
+	NAME:OFFSET:LENGTH をカンマ区切りで複数指定可能
+	*/
+	fn load_process_sections(key: &str, text: &str) -> Vec<(String,i32,i32)> {
+		let mut sections = vec![];
+		for item in text.split(',') {
+			let fields: Vec<&str> = item.split(':').collect();
+			if fields.len() != 3 {
+				panic!("invalid {}: {}", key, item);
+			}
+			let offset = Settings::load_process_i32(key, fields[1]);
+			let length = Settings::load_process_i32(key, fields[2]);
+			sections.push((fields[0].to_string(), offset, length));
+		}
+		sections
+	}
+
+	fn load_process_arrival_mode(text: &str) -> ArrivalMode {
+		match text {
+			"dense" => ArrivalMode::DENSE,
+			"random" => ArrivalMode::RANDOM,
+			"list" => ArrivalMode::LIST,
+			_ => panic!("invalid sporadic: {}", text),
+		}
+	}
+
+	fn load_process_exec_mode(text: &str) -> Option<ExecTimeMode> {
+		match text {
+			"cyclic" => None,
+			"uniform" => Some(ExecTimeMode::UNIFORM),
+			"normal" => Some(ExecTimeMode::NORMAL),
+			"hist" => Some(ExecTimeMode::HISTOGRAM),
+			"choice" => Some(ExecTimeMode::CHOICE),
+			_ => panic!("invalid exec_mode: {}", text),
+		}
+	}
+
+	/**
+	処理時間分布の値解析
+	VALUE:WEIGHT をカンマ区切りで複数指定可能
+	*/
+	fn load_process_exec_values(key: &str, text: &str) -> Vec<(i32, i32)> {
+		let mut values = vec![];
+		for item in text.split(',') {
+			let fields: Vec<&str> = item.split(':').collect();
+			if fields.len() != 2 {
+				panic!("invalid {}: {}", key, item);
+			}
+			values.push((Settings::load_process_i32(key, fields[0]), Settings::load_process_i32(key, fields[1])));
+		}
+		values
+	}
+
+	/**
+	測定値ファイル解析
+	PATH[:COLUMN] 1行1値、またはCSVのCOLUMN列(1始まりの列番号 or 先頭行の列名)を読み出す
+	PATHは設定ファイルからの相対パス, 数値でない行と#始まりの行はスキップする
+	*/
+	fn load_process_time_file(&self, text: &str) -> Vec<i32> {
+		let (path, column) = match text.rsplit_once(':') {
+			// Windowsのドライブレター(C:\...)は列指定ではない
+			Some((path, column)) if !column.contains(['/', '\\']) => (path, Some(column)),
+			_ => (text, None),
+		};
+		let path = self.base_dir.join(path);
+		let content = match std::fs::read_to_string(&path) {
+			Ok(content) => content,
+			Err(why) => panic!("couldn't open time_file {}: {}", path.display(), why),
+		};
+		let split = |line: &str| -> Vec<String> {
+			line.split([',', '\t']).map(|field| field.trim().trim_matches('"').to_string()).collect()
+		};
+		let lines: Vec<&str> = content.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')).collect();
+		// 列番号(0始まり)
+		let col = match column {
+			None => 0,
+			Some(column) => match column.parse::<usize>() {
+				Ok(num) if num > 0 => num - 1,
+				Ok(_) => panic!("invalid time_file column: {}", column),
+				Err(_) => match lines.first().and_then(|header| split(header).iter().position(|field| field == column)) {
+					Some(col) => col,
+					None => panic!("time_file column not found: {}", column),
+				},
+			},
+		};
+		let mut values = vec![];
+		for line in lines.iter() {
+			if let Some(Ok(value)) = split(line).get(col).map(|field| field.parse::<f64>()) {
+				values.push(value.round() as i32);
+			}
+		}
+		if values.is_empty() {
+			panic!("no execution time in time_file: {}", path.display());
+		}
+		values
+	}
+
+	fn load_process_jitter_mode(text: &str) -> JitterMode {
+		match text {
+			"max" => JitterMode::MAX,
+			"alt" => JitterMode::ALTERNATE,
+			"random" => JitterMode::RANDOM,
+			_ => panic!("invalid jitter_mode: {}", text),
+		}
+	}
+
+	fn load_process_i32_list(key: &str, text: &str) -> Vec<i32> {
+		text.split(',').map(|val| Settings::load_process_i32(key, val)).collect()
+	}
+
+	fn load_process_i32(key: &str, text: &str) -> i32 {
+		match text.parse::<i32>() {
+			Ok(val) => val,
+			Err(_) => panic!("invalid {}: {}", key, text),
+		}
+	}
+
+	/**
+	周波数(MHz)解析, 0以下は不可
+	*/
+	fn load_freq(key: &str, text: &str) -> i32 {
+		match text.parse::<i32>() {
+			Ok(freq) if freq > 0 => freq,
+			_ => panic!("invalid {}: {}", key, text),
+		}
+	}
+
+	fn load_process_f64(key: &str, text: &str) -> f64 {
+		match text.parse::<f64>() {
+			Ok(val) => val,
+			Err(_) => panic!("invalid {}: {}", key, text),
+		}
+	}
+
 	fn load_process_intr_task(text: &str) -> ProcessKind {
 		match text {
 			"INTR" => ProcessKind::INTR,
@@ -275,6 +1236,22 @@ impl Settings
 		}
 	}
 
+	fn load_sched_policy(text: &str) -> Result<SchedPolicy,String> {
+		match text {
+			"FP" => Ok(SchedPolicy::FP),
+			"EDF" => Ok(SchedPolicy::EDF),
+			_ => Err(format!("invalid SchedPolicy: {}", text)),
+		}
+	}
+
+	fn load_core_assign(text: &str) -> Result<CoreAssign,String> {
+		match text {
+			"PARTITIONED" => Ok(CoreAssign::PARTITIONED),
+			"GLOBAL" => Ok(CoreAssign::GLOBAL),
+			_ => Err(format!("invalid CoreAssign: {}", text)),
+		}
+	}
+
 	fn load_bool(text: &str) -> Result<bool,String> {
 		match text {
 			"true" => Ok(true),